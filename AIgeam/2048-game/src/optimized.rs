@@ -3,6 +3,8 @@ use tokio::sync::Mutex;
 use serde::{Serialize, Deserialize};
 use wasm_bindgen::prelude::*;
 use web_sys::console;
+// 道具的定义、发放和效果都在 games/2048/wasm-2048 的 power_ups 模块里
+use wasm_2048::PowerUp;
 
 #[wasm_bindgen]
 pub struct OptimizedGame2048 {
@@ -58,21 +60,15 @@ impl OptimizedGame2048 {
 pub struct EnhancedGameState {
     grid: [[u32; 4]; 4],
     score: u32,
+    // 当前连击数，引擎里对应 GameStats::combo
     combo: u32,
     power_ups: Vec<PowerUp>,
+    // 限时模式的剩余时间，由 games/2048/wasm-2048 的 TimeAttack 维护（time_remaining_ms）
     time_remaining: u32,
     game_mode: GameMode,
     player_stats: PlayerStats,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-pub enum PowerUp {
-    DoublePoints { duration: u32 },
-    UndoLastMove,
-    ShuffleBoard,
-    FreezeTime { duration: u32 },
-}
-
 #[derive(Serialize, Deserialize, Clone)]
 pub enum GameMode {
     Classic,
@@ -85,6 +81,7 @@ pub enum GameMode {
 pub struct PlayerStats {
     total_moves: u32,
     highest_tile: u32,
+    // 本局最长连击，引擎里对应 GameStats::longest_combo
     longest_combo: u32,
     fastest_time: u32,
 } 
\ No newline at end of file
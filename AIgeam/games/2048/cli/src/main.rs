@@ -0,0 +1,172 @@
+use std::io::{self, BufRead, Write};
+
+use game_2048_core::{exponent_to_value, next_status, Direction, Grid, Status, CLASSIC_SPAWN_WEIGHTS};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+
+// 供 TypeScript 服务端调用的 2048 规则：标准输入每行一个 JSON 请求，标准输出每行一个 JSON 应答
+// {"op": "move", "grid": [[2,2,0,0],...], "direction": "left"}
+//   -> {"grid": [[4,0,0,0],...], "moved": true, "score": 4}
+// {"op": "spawn", "grid": [...], "seed": 42}
+//   -> {"grid": [...], "spawned": {"row": 1, "col": 3, "value": 2}}
+// {"op": "status", "grid": [...], "win_tile": 2048}
+//   -> {"status": "Playing", "won": false}
+//   won 只看棋盘上是否已有目标方块：合成出目标后无路可走时 status 为 Lost，won 仍为 true
+// 出错时应答 {"error": "..."}
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Request {
+    Move { grid: Vec<Vec<u32>>, direction: String },
+    Spawn { grid: Vec<Vec<u32>>, seed: u64 },
+    Status { grid: Vec<Vec<u32>>, #[serde(default = "default_win_tile")] win_tile: u32 },
+}
+
+#[derive(Serialize)]
+struct SpawnedTile {
+    row: usize,
+    col: usize,
+    value: u32,
+}
+
+fn default_win_tile() -> u32 {
+    2048
+}
+
+fn parse_direction(name: &str) -> Result<Direction, String> {
+    match name {
+        "up" => Ok(Direction::Up),
+        "right" => Ok(Direction::Right),
+        "down" => Ok(Direction::Down),
+        "left" => Ok(Direction::Left),
+        _ => Err(format!("Unknown direction {}", name)),
+    }
+}
+
+fn handle(request: Request) -> Result<serde_json::Value, String> {
+    match request {
+        Request::Move { grid, direction } => {
+            let grid = Grid::from_values(&grid)?;
+            let direction = parse_direction(&direction)?;
+            let (moved, score) = grid.execute_move(direction.code());
+            Ok(json!({ "grid": moved.to_values(), "moved": moved != grid, "score": score }))
+        }
+        Request::Spawn { grid, seed } => {
+            let mut grid = Grid::from_values(&grid)?;
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let spawned = game_2048_core::choose_spawn(&grid.empty_cells(), &CLASSIC_SPAWN_WEIGHTS, &mut rng)
+                .map(|(row, col, exponent)| {
+                    grid.set(row, col, exponent as u8);
+                    SpawnedTile { row, col, value: exponent_to_value(exponent) }
+                });
+            Ok(json!({ "grid": grid.to_values(), "spawned": spawned }))
+        }
+        Request::Status { grid, win_tile } => {
+            if !win_tile.is_power_of_two() || win_tile < 2 {
+                return Err(format!("Win tile {} must be a power of two", win_tile));
+            }
+            let grid = Grid::from_values(&grid)?;
+            let won = grid.max_exponent() as u32 >= win_tile.trailing_zeros();
+            let status = next_status(
+                Status::Playing,
+                grid.has_legal_move(),
+                grid.max_exponent() as u32,
+                win_tile.trailing_zeros(),
+            );
+            Ok(json!({ "status": status, "won": won }))
+        }
+    }
+}
+
+// 一行请求对应一行应答，无法解析的请求同样应答 error
+fn respond(line: &str) -> serde_json::Value {
+    serde_json::from_str::<Request>(line)
+        .map_err(|e| e.to_string())
+        .and_then(handle)
+        .unwrap_or_else(|error| json!({ "error": error }))
+}
+
+fn main() -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(stdout, "{}", respond(&line))?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(grid: Vec<Vec<u32>>) -> serde_json::Value {
+        handle(Request::Status { grid, win_tile: 2048 }).unwrap()
+    }
+
+    #[test]
+    fn full_board_with_the_win_tile_is_lost_but_won() {
+        let grid = vec![vec![2048, 2, 4, 2], vec![2, 4, 2, 4], vec![4, 2, 4, 2], vec![2, 4, 2, 4]];
+        assert_eq!(status(grid), json!({ "status": "Lost", "won": true }));
+    }
+
+    #[test]
+    fn status_reports_win_while_moves_remain() {
+        let grid = vec![vec![2048, 2, 0, 0], vec![0; 4], vec![0; 4], vec![0; 4]];
+        assert_eq!(status(grid), json!({ "status": "Won", "won": true }));
+        let grid = vec![vec![1024, 2, 0, 0], vec![0; 4], vec![0; 4], vec![0; 4]];
+        assert_eq!(status(grid), json!({ "status": "Playing", "won": false }));
+    }
+
+    #[test]
+    fn legal_move_slides_merges_and_scores() {
+        let response = respond(r#"{"op": "move", "grid": [[2,2,0,4],[0,0,0,0],[0,0,0,0],[0,0,0,0]], "direction": "left"}"#);
+        assert_eq!(
+            response,
+            json!({ "grid": [[4,4,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]], "moved": true, "score": 4 })
+        );
+    }
+
+    #[test]
+    fn illegal_move_leaves_the_grid_unchanged() {
+        let response = respond(r#"{"op": "move", "grid": [[2,4,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]], "direction": "left"}"#);
+        assert_eq!(
+            response,
+            json!({ "grid": [[2,4,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]], "moved": false, "score": 0 })
+        );
+    }
+
+    #[test]
+    fn spawn_fills_an_empty_cell_reproducibly() {
+        let request = r#"{"op": "spawn", "grid": [[2,4,2,4],[4,2,4,2],[2,4,0,4],[4,2,4,2]], "seed": 42}"#;
+        let response = respond(request);
+        assert_eq!(response, respond(request));
+        let value = response["spawned"]["value"].as_u64().unwrap();
+        assert!(value == 2 || value == 4);
+        assert_eq!(response["spawned"]["row"], 2);
+        assert_eq!(response["spawned"]["col"], 2);
+        assert_eq!(response["grid"][2][2], value);
+
+        let full = respond(r#"{"op": "spawn", "grid": [[2,4,2,4],[4,2,4,2],[2,4,2,4],[4,2,4,2]], "seed": 42}"#);
+        assert_eq!(full["spawned"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn malformed_requests_get_an_error() {
+        for line in [
+            "not json",
+            r#"{"op": "jump", "grid": [[0,0,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]]}"#,
+            r#"{"op": "move", "grid": [[0,0,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]]}"#,
+            r#"{"op": "move", "grid": [[0,0,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]], "direction": "sideways"}"#,
+            r#"{"op": "move", "grid": [[3,0,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]], "direction": "left"}"#,
+            r#"{"op": "spawn", "grid": [[0,0,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]], "seed": -1}"#,
+        ] {
+            assert!(respond(line)["error"].is_string(), "{}", line);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+// 编码固定为 0上 1右 2下 3左，wasm 接口、录像和 AI 存档都使用这个编码
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_code(code: u8) -> Option<Direction> {
+        Self::ALL.get(code as usize).copied()
+    }
+}
@@ -0,0 +1,120 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::line::{line_layout, line_position, slide_line};
+use crate::tile::{exponent_to_value, value_to_exponent};
+
+// 任意尺寸棋盘的通用实现，每格存放方块指数（0为空）
+
+// 方块数值使用 u32，指数超过31就无法表示
+pub const MAX_GRID_EXPONENT: u8 = 31;
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid {
+    rows: usize,
+    cols: usize,
+    cells: Vec<u8>,
+}
+
+impl Grid {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            cells: vec![0; rows * cols],
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> u8 {
+        self.cells[row * self.cols + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, exponent: u8) {
+        self.cells[row * self.cols + col] = exponent;
+    }
+
+    // 方向编码与 move_tiles 一致：0上 1右 2下 3左
+    pub fn execute_move(&self, direction: u8) -> (Grid, u32) {
+        let mut result = self.clone();
+        let mut gained = 0;
+        let (lines, length) = match line_layout(self.rows, self.cols, direction) {
+            Some(layout) => layout,
+            None => return (result, 0),
+        };
+
+        let mut line = Vec::with_capacity(length);
+        for l in 0..lines {
+            line.clear();
+            line.extend((0..length).map(|k| self.get_along(direction, l, k)));
+            gained += slide_line(&mut line, MAX_GRID_EXPONENT);
+            for (k, &exponent) in line.iter().enumerate() {
+                result.set_along(direction, l, k, exponent);
+            }
+        }
+
+        (result, gained)
+    }
+
+    fn get_along(&self, direction: u8, l: usize, k: usize) -> u8 {
+        let (i, j) = line_position(self.rows, self.cols, direction, l, k);
+        self.get(i, j)
+    }
+
+    fn set_along(&mut self, direction: u8, l: usize, k: usize, exponent: u8) {
+        let (i, j) = line_position(self.rows, self.cols, direction, l, k);
+        self.set(i, j, exponent);
+    }
+
+    pub fn empty_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.rows)
+            .flat_map(|i| (0..self.cols).map(move |j| (i, j)))
+            .filter(|&(i, j)| self.get(i, j) == 0)
+            .collect()
+    }
+
+    pub fn max_exponent(&self) -> u8 {
+        self.cells.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn can_move(&self, direction: u8) -> bool {
+        self.execute_move(direction).0 != *self
+    }
+
+    pub fn has_legal_move(&self) -> bool {
+        (0..4).any(|direction| self.can_move(direction))
+    }
+
+    // 从方块数值（0 为空）构造，数值必须是 2 的幂且不超过 2^MAX_GRID_EXPONENT
+    pub fn from_values(values: &[Vec<u32>]) -> Result<Self, &'static str> {
+        let rows = values.len();
+        let cols = values.first().map_or(0, |row| row.len());
+        if rows == 0 || cols == 0 || values.iter().any(|row| row.len() != cols) {
+            return Err("Grid must be a non-empty rectangle");
+        }
+        let mut grid = Self::new(rows, cols);
+        for (i, row) in values.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                if value == 1 || !(value == 0 || value.is_power_of_two()) {
+                    return Err("Grid contains an invalid tile value");
+                }
+                grid.set(i, j, value_to_exponent(value) as u8);
+            }
+        }
+        Ok(grid)
+    }
+
+    pub fn to_values(&self) -> Vec<Vec<u32>> {
+        (0..self.rows)
+            .map(|i| (0..self.cols).map(|j| exponent_to_value(self.get(i, j) as u32)).collect())
+            .collect()
+    }
+}
+
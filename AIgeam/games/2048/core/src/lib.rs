@@ -0,0 +1,19 @@
+// 2048 规则的唯一实现：方向、单行移动与合并、任意尺寸棋盘、新方块生成、得分和胜负判断
+// wasm 绑定、AI 和命令行工具都依赖这里，不依赖 std，只需要 alloc
+// 开启 serde feature 时方向和棋盘可以序列化
+#![no_std]
+extern crate alloc;
+
+mod direction;
+mod grid;
+mod line;
+mod spawn;
+mod status;
+mod tile;
+
+pub use direction::Direction;
+pub use grid::{Grid, MAX_GRID_EXPONENT};
+pub use line::{line_layout, line_position, slide_line, trace_line, LineStep};
+pub use spawn::{choose_spawn, spawn_exponent, CLASSIC_SPAWN_WEIGHTS};
+pub use status::{next_status, Status};
+pub use tile::{exponent_to_value, value_to_exponent};
@@ -0,0 +1,96 @@
+use alloc::vec::Vec;
+
+// 某个方向上的线条数和每条线的长度
+pub fn line_layout(rows: usize, cols: usize, direction: u8) -> Option<(usize, usize)> {
+    match direction {
+        0 | 2 => Some((cols, rows)),
+        1 | 3 => Some((rows, cols)),
+        _ => None,
+    }
+}
+
+// 第 l 条线上、从移动方向的目标边开始数第 k 个格子
+pub fn line_position(rows: usize, cols: usize, direction: u8, l: usize, k: usize) -> (usize, usize) {
+    match direction {
+        0 => (k, l),
+        1 => (l, cols - 1 - k),
+        2 => (rows - 1 - k, l),
+        _ => (l, k),
+    }
+}
+
+// 一条线上每个方块在移动中的去向，位置均为从目标边开始的下标
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineStep {
+    Slide { from: usize, to: usize },
+    Merge { first: usize, second: usize, to: usize, exponent: u8 },
+}
+
+// 追踪一条线在移动后每个方块的去向，规则与 slide_line 完全一致
+pub fn trace_line(line: &[u8], max_exponent: u8) -> Vec<LineStep> {
+    let mut steps = Vec::new();
+    let mut write = 0;
+    let mut pending: Option<(usize, u8)> = None;
+
+    for (read, &exponent) in line.iter().enumerate() {
+        if exponent == 0 {
+            continue;
+        }
+        match pending {
+            Some((first, p)) if p == exponent && p < max_exponent => {
+                steps.push(LineStep::Merge { first, second: read, to: write, exponent: p + 1 });
+                write += 1;
+                pending = None;
+            }
+            Some((from, _)) => {
+                steps.push(LineStep::Slide { from, to: write });
+                write += 1;
+                pending = Some((read, exponent));
+            }
+            None => pending = Some((read, exponent)),
+        }
+    }
+    if let Some((from, _)) = pending {
+        steps.push(LineStep::Slide { from, to: write });
+    }
+
+    steps
+}
+
+// 把一条线上的指数压缩到开头并合并相邻相同的方块，返回得分
+// 达到 max_exponent 的方块不再合并
+pub fn slide_line(line: &mut [u8], max_exponent: u8) -> u32 {
+    let mut gained = 0;
+    let mut write = 0;
+    let mut pending: Option<u8> = None;
+
+    for read in 0..line.len() {
+        let exponent = line[read];
+        if exponent == 0 {
+            continue;
+        }
+        match pending {
+            Some(p) if p == exponent && p < max_exponent => {
+                line[write] = p + 1;
+                gained += 1u32 << (p + 1);
+                write += 1;
+                pending = None;
+            }
+            Some(p) => {
+                line[write] = p;
+                write += 1;
+                pending = Some(exponent);
+            }
+            None => pending = Some(exponent),
+        }
+    }
+    if let Some(p) = pending {
+        line[write] = p;
+        write += 1;
+    }
+    for cell in &mut line[write..] {
+        *cell = 0;
+    }
+
+    gained
+}
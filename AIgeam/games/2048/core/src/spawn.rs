@@ -0,0 +1,38 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// 经典规则：90% 生成 2，10% 生成 4
+pub const CLASSIC_SPAWN_WEIGHTS: [f32; 2] = [0.9, 0.1];
+
+// 权重换算成整数比例时的精度（百万分之一）
+const WEIGHT_PARTS: f64 = 1_000_000.0;
+
+// 按权重挑选新方块的指数，weights[i] 是生成 2^(i+1) 的权重
+// 从小到大依次用 gen_ratio 决定是否选中当前指数，最后一个有权重的指数不再抽取；
+// 经典权重只抽取一次 gen_ratio(900000, 1000000)，与最初版本的 gen_bool(0.9) 消耗相同的随机数、得到相同的结果，
+// 旧种子的对局和录像因此可以继续复现
+pub fn spawn_exponent(weights: &[f32], rng: &mut impl Rng) -> u32 {
+    let total: f64 = weights.iter().map(|&w| w as f64).sum();
+    let parts = |weight: f32| (weight as f64 / total * WEIGHT_PARTS + 0.5) as u32;
+
+    let last = match weights.iter().rposition(|&w| parts(w) > 0) {
+        Some(last) => last,
+        // 权重全部低于精度时退回到最后一个正权重，总和为 0 时生成 2
+        None => return weights.iter().rposition(|&w| w > 0.0).unwrap_or(0) as u32 + 1,
+    };
+    let mut remaining: u32 = weights[..=last].iter().map(|&w| parts(w)).sum();
+    for (i, &weight) in weights[..last].iter().enumerate() {
+        let part = parts(weight);
+        if part > 0 && rng.gen_ratio(part, remaining) {
+            return i as u32 + 1;
+        }
+        remaining -= part;
+    }
+    last as u32 + 1
+}
+
+// 先随机挑一个空格，再按权重挑指数；随机数的使用顺序是录像和种子复现的一部分，不能改动
+pub fn choose_spawn(empty_cells: &[(usize, usize)], weights: &[f32], rng: &mut impl Rng) -> Option<(usize, usize, u32)> {
+    let &(i, j) = empty_cells.choose(rng)?;
+    Some((i, j, spawn_exponent(weights, rng)))
+}
@@ -0,0 +1,25 @@
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Status {
+    Playing,
+    Won,
+    // 达到目标后选择继续游戏
+    ContinuedAfterWin,
+    Lost,
+}
+
+// 每步之后的状态：无路可走即失败；第一次达到目标方块为胜利，之后继续游戏
+pub fn next_status(previous: Status, has_legal_move: bool, max_exponent: u32, win_exponent: u32) -> Status {
+    if !has_legal_move {
+        Status::Lost
+    } else {
+        match previous {
+            Status::Won | Status::ContinuedAfterWin => Status::ContinuedAfterWin,
+            _ if max_exponent >= win_exponent => Status::Won,
+            _ => Status::Playing,
+        }
+    }
+}
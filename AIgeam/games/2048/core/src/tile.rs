@@ -0,0 +1,8 @@
+// 方块在规则里以指数表示：0 为空，1 为 2，2 为 4 ...
+pub fn exponent_to_value(exponent: u32) -> u32 {
+    if exponent == 0 { 0 } else { 1 << exponent }
+}
+
+pub fn value_to_exponent(value: u32) -> u32 {
+    if value == 0 { 0 } else { value.trailing_zeros() }
+}
@@ -0,0 +1,134 @@
+// 规则库的公共测试：wasm 引擎、AI、命令行工具和 TS 服务端都以这里的行为为准
+use game_2048_core::{
+    choose_spawn, next_status, slide_line, spawn_exponent, trace_line, Grid, LineStep, Status, CLASSIC_SPAWN_WEIGHTS,
+    MAX_GRID_EXPONENT,
+};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+#[test]
+fn classic_spawns_draw_like_the_original_gen_bool() {
+    let cells: Vec<(usize, usize)> = (0..4).flat_map(|i| (0..4).map(move |j| (i, j))).collect();
+    for seed in 0..200 {
+        let mut original = ChaCha8Rng::seed_from_u64(seed);
+        let mut current = ChaCha8Rng::seed_from_u64(seed);
+        for empty in 1..=cells.len() {
+            let &(i, j) = cells[..empty].choose(&mut original).unwrap();
+            let exponent = if original.gen_bool(0.9) { 1 } else { 2 };
+            assert_eq!(choose_spawn(&cells[..empty], &CLASSIC_SPAWN_WEIGHTS, &mut current), Some((i, j, exponent)));
+        }
+        assert_eq!(original.gen::<u64>(), current.gen::<u64>());
+    }
+}
+
+#[test]
+fn slide_line_compresses_and_merges_each_tile_once() {
+    let cases: [([u8; 4], [u8; 4], u32); 5] = [
+        ([1, 1, 2, 2], [2, 3, 0, 0], 4 + 8),
+        ([1, 1, 1, 0], [2, 1, 0, 0], 4),
+        ([2, 1, 1, 0], [2, 2, 0, 0], 4),
+        ([0, 0, 0, 3], [3, 0, 0, 0], 0),
+        ([1, 2, 1, 2], [1, 2, 1, 2], 0),
+    ];
+    for (line, expected, gained) in cases {
+        let mut moved = line;
+        assert_eq!(slide_line(&mut moved, MAX_GRID_EXPONENT), gained, "{:?}", line);
+        assert_eq!(moved, expected, "{:?}", line);
+    }
+}
+
+#[test]
+fn tiles_at_the_max_exponent_do_not_merge() {
+    let mut line = [4, 4, 3, 3];
+    assert_eq!(slide_line(&mut line, 4), 16);
+    assert_eq!(line, [4, 4, 4, 0]);
+    assert_eq!(trace_line(&[4, 4, 3, 3], 4), vec![
+        LineStep::Slide { from: 0, to: 0 },
+        LineStep::Slide { from: 1, to: 1 },
+        LineStep::Merge { first: 2, second: 3, to: 2, exponent: 4 },
+    ]);
+}
+
+// trace_line 描述的去向重建出的线条必须和 slide_line 的结果一致
+#[test]
+fn trace_line_matches_slide_line() {
+    for code in 0..4u32.pow(5) {
+        let line: Vec<u8> = (0..5).map(|k| (code / 4u32.pow(k) % 4) as u8).collect();
+        for max_exponent in [2, MAX_GRID_EXPONENT] {
+            let mut slid = line.clone();
+            slide_line(&mut slid, max_exponent);
+            let mut traced = vec![0; line.len()];
+            for step in trace_line(&line, max_exponent) {
+                match step {
+                    LineStep::Slide { from, to } => traced[to] = line[from],
+                    LineStep::Merge { first, second, to, exponent } => {
+                        assert_eq!((line[first], line[second]), (exponent - 1, exponent - 1));
+                        traced[to] = exponent;
+                    }
+                }
+            }
+            assert_eq!(traced, slid, "{:?}", line);
+        }
+    }
+}
+
+#[test]
+fn grid_moves_in_every_direction_on_rectangular_boards() {
+    let grid = Grid::from_values(&[vec![2, 2, 0, 4, 4], vec![0, 2, 0, 0, 4], vec![2, 0, 0, 8, 8]]).unwrap();
+    let expected = [
+        (vec![vec![4, 4, 0, 4, 8], vec![0, 0, 0, 8, 8], vec![0, 0, 0, 0, 0]], 4 + 4 + 8),
+        (vec![vec![0, 0, 0, 4, 8], vec![0, 0, 0, 2, 4], vec![0, 0, 0, 2, 16]], 4 + 8 + 16),
+        (vec![vec![0, 0, 0, 0, 0], vec![0, 0, 0, 4, 8], vec![4, 4, 0, 8, 8]], 4 + 4 + 8),
+        (vec![vec![4, 8, 0, 0, 0], vec![2, 4, 0, 0, 0], vec![2, 16, 0, 0, 0]], 4 + 8 + 16),
+    ];
+    for (direction, (values, score)) in expected.into_iter().enumerate() {
+        let (moved, gained) = grid.execute_move(direction as u8);
+        assert_eq!((moved.to_values(), gained), (values, score), "direction {}", direction);
+    }
+
+    // 未知方向不改变棋盘
+    assert_eq!(grid.execute_move(4), (grid.clone(), 0));
+}
+
+#[test]
+fn packed_board_has_no_legal_move() {
+    let grid = Grid::from_values(&[vec![2, 4, 2], vec![4, 2, 4], vec![2, 4, 2]]).unwrap();
+    assert!(!grid.has_legal_move());
+    assert!(grid.empty_cells().is_empty());
+    assert!(Grid::from_values(&[vec![2, 3]]).is_err());
+    assert!(Grid::from_values(&[vec![2, 2], vec![2]]).is_err());
+}
+
+#[test]
+fn spawn_weights_pick_the_weighted_exponents() {
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    let fours = (0..10_000).filter(|_| spawn_exponent(&CLASSIC_SPAWN_WEIGHTS, &mut rng) == 2).count();
+    assert!((800..1200).contains(&fours), "{} fours", fours);
+
+    assert!((0..100).all(|_| spawn_exponent(&[0.0, 1.0], &mut rng) == 2));
+    assert!((0..100).all(|_| spawn_exponent(&[0.0, 0.0, 3.0], &mut rng) == 3));
+    // 低于精度的权重永远不会抽中
+    assert!((0..100).all(|_| spawn_exponent(&[1.0, 1e-9], &mut rng) == 1));
+}
+
+#[test]
+fn zero_weights_fall_back_without_drawing() {
+    let mut rng = ChaCha8Rng::seed_from_u64(4);
+    let before = rng.clone().gen::<u64>();
+    assert_eq!(spawn_exponent(&[0.0, 0.0], &mut rng), 1);
+    assert_eq!(spawn_exponent(&[], &mut rng), 1);
+    assert_eq!(rng.gen::<u64>(), before);
+    assert_eq!(choose_spawn(&[], &CLASSIC_SPAWN_WEIGHTS, &mut rng), None);
+}
+
+#[test]
+fn status_follows_win_and_loss() {
+    assert_eq!(next_status(Status::Playing, true, 10, 11), Status::Playing);
+    assert_eq!(next_status(Status::Playing, true, 11, 11), Status::Won);
+    assert_eq!(next_status(Status::Won, true, 11, 11), Status::ContinuedAfterWin);
+    assert_eq!(next_status(Status::ContinuedAfterWin, true, 12, 11), Status::ContinuedAfterWin);
+    // 无路可走时即使已经达到目标也判为失败
+    assert_eq!(next_status(Status::Playing, false, 11, 11), Status::Lost);
+    assert_eq!(next_status(Status::Won, false, 11, 11), Status::Lost);
+}
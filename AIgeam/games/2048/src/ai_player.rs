@@ -1,16 +1,53 @@
 use serde::{Serialize, Deserialize};
-use rand::Rng;
-use std::collections::HashMap;
+use rand::{Rng, SeedableRng};
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use std::sync::Arc;
+use std::time::Duration;
+use game_2048_core::{self as rules, MAX_GRID_EXPONENT};
+
+mod analysis;
+mod calibration;
+mod clock;
+mod evaluator;
+mod expectimax;
+mod headless;
+mod monte_carlo;
+mod ntuple;
+mod persistence;
+
+pub use analysis::{DirectionAnalysis, MoveAnalysis};
+pub use calibration::{Handicap, PlayerGame, SkillCalibrator};
+pub use clock::Clock;
+#[cfg(not(target_arch = "wasm32"))]
+pub use clock::SystemClock;
+pub use evaluator::{
+    BoardEvaluator, EdgeValue, EmptyCells, MergePotential, Monotonicity, SnakePattern, WeightedEvaluator,
+};
+use expectimax::Expectimax;
+pub use headless::HeadlessGame;
+pub use ntuple::NTupleNetwork;
+
+// 限时搜索时迭代加深的最大层数
+const MAX_SEARCH_DEPTH: u32 = 8;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct AIPlayer {
     difficulty: AILevel,
     learning_rate: f32,
-    experience: HashMap<BoardState, Vec<(Direction, f32)>>,
     current_strategy: Strategy,
+    // AI 自身的随机数（探索、随机模拟），可以固定种子以复现对局
+    #[serde(skip, default = "ChaCha8Rng::from_entropy")]
+    rng: ChaCha8Rng,
+    // 每一步改走随机合法方向的概率，用来按玩家水平削弱 AI
+    #[serde(default)]
+    noise: f32,
+    // 局面评估函数，贪心和期望最大搜索共用；序列化时不保存，恢复后为默认组合
+    #[serde(skip, default = "evaluator::default_evaluator")]
+    evaluator: Arc<dyn BoardEvaluator>,
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub enum AILevel {
     Easy,
     Medium,
@@ -18,29 +55,88 @@ pub enum AILevel {
     Adaptive,
 }
 
-#[derive(Hash, Eq, PartialEq, Serialize, Deserialize, Clone)]
-struct BoardState {
-    grid: [[u32; 4]; 4],
-    score: u32,
+#[derive(Hash, Eq, PartialEq, Serialize, Deserialize, Clone, Debug)]
+pub struct BoardState {
+    pub grid: Vec<Vec<u32>>,
+    pub score: u32,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
-pub enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
+impl BoardState {
+    fn rows(&self) -> usize {
+        self.grid.len()
+    }
+
+    fn cols(&self) -> usize {
+        self.grid.first().map_or(0, |row| row.len())
+    }
+
+    fn cell_count(&self) -> usize {
+        self.rows() * self.cols()
+    }
+
+    fn corners(&self) -> [u32; 4] {
+        let (last_row, last_col) = (self.rows() - 1, self.cols() - 1);
+        [
+            self.grid[0][0],
+            self.grid[0][last_col],
+            self.grid[last_row][0],
+            self.grid[last_row][last_col],
+        ]
+    }
+
+    fn empty_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.rows())
+            .flat_map(|i| (0..self.cols()).map(move |j| (i, j)))
+            .filter(|&(i, j)| self.grid[i][j] == 0)
+            .collect()
+    }
+
+    fn with_tile(&self, row: usize, col: usize, value: u32) -> BoardState {
+        let mut next = self.clone();
+        next.grid[row][col] = value;
+        next
+    }
+
+    // 模拟一次移动（不生成新方块），棋盘没有变化时返回 None；逐行的移动与合并使用规则库
+    fn apply_move(&self, direction: Direction) -> Option<BoardState> {
+        let (rows, cols) = (self.rows(), self.cols());
+        let (lines, length) = rules::line_layout(rows, cols, direction.code())?;
+        let position = |l: usize, k: usize| rules::line_position(rows, cols, direction.code(), l, k);
+
+        let mut next = self.clone();
+        let mut line = Vec::with_capacity(length);
+        for l in 0..lines {
+            line.clear();
+            line.extend((0..length).map(|k| {
+                let (i, j) = position(l, k);
+                rules::value_to_exponent(self.grid[i][j]) as u8
+            }));
+            next.score += rules::slide_line(&mut line, MAX_GRID_EXPONENT);
+            for (k, &exponent) in line.iter().enumerate() {
+                let (i, j) = position(l, k);
+                next.grid[i][j] = rules::exponent_to_value(exponent as u32);
+            }
+        }
+
+        if next.grid == self.grid { None } else { Some(next) }
+    }
 }
 
+pub use game_2048_core::Direction;
+
 #[derive(Clone, Serialize, Deserialize)]
 enum Strategy {
     CornerMax,
-    SnakePattern,
-    MonotonicRows,
+    // 只看一步：选择移动后局面评估最高的方向
+    Greedy,
     AdaptiveLearning {
-        weights: Vec<f32>,
-        last_move: Option<Direction>,
-        last_state: Option<BoardState>,
+        network: NTupleNetwork,
+        last_afterstate: Option<BoardState>,
+    },
+    Expectimax(Expectimax),
+    MonteCarlo {
+        rollouts: u32,
+        depth: u32,
     },
 }
 
@@ -52,41 +148,134 @@ impl AIPlayer {
                 AILevel::Easy => 0.1,
                 AILevel::Medium => 0.2,
                 AILevel::Hard => 0.3,
-                AILevel::Adaptive => 0.4,
+                AILevel::Adaptive => 0.1,
+            },
+            rng: ChaCha8Rng::from_entropy(),
+            noise: 0.0,
+            evaluator: match difficulty {
+                AILevel::Medium => Arc::new(
+                    WeightedEvaluator::new()
+                        .with(2.7, EmptyCells)
+                        .with(1.0, Monotonicity)
+                        .with(1.0, MergePotential)
+                        .with(1.0, SnakePattern),
+                ),
+                _ => evaluator::default_evaluator(),
             },
-            experience: HashMap::new(),
             current_strategy: match difficulty {
                 AILevel::Easy => Strategy::CornerMax,
-                AILevel::Medium => Strategy::SnakePattern,
-                AILevel::Hard => Strategy::MonotonicRows,
+                AILevel::Medium => Strategy::Greedy,
+                AILevel::Hard => Strategy::Expectimax(Expectimax::new(3, 0.0001)),
                 AILevel::Adaptive => Strategy::AdaptiveLearning {
-                    weights: vec![1.0, 1.0, 1.0, 1.0],
-                    last_move: None,
-                    last_state: None,
+                    network: NTupleNetwork::with_default_tuples(),
+                    last_afterstate: None,
                 },
             },
         }
     }
 
-    pub fn get_next_move(&mut self, state: &BoardState) -> Direction {
+    // 随机模拟对局的 AI，rollouts 越多越强，适合按时间/算力预算调节难度
+    pub fn with_rollout_budget(rollouts: u32, depth: u32) -> Self {
+        let mut player = Self::new(AILevel::Adaptive);
+        player.current_strategy = Strategy::MonteCarlo { rollouts, depth };
+        player
+    }
+
+    // 替换局面评估函数，例如 WeightedEvaluator 组合或自定义实现
+    pub fn with_evaluator(mut self, evaluator: impl BoardEvaluator + 'static) -> Self {
+        self.evaluator = Arc::new(evaluator);
+        self
+    }
+
+    // 设置削弱程度：期望最大搜索改用指定深度，其余策略把深度折算进随机走子的概率
+    pub fn set_handicap(&mut self, handicap: Handicap) {
         match &mut self.current_strategy {
+            Strategy::Expectimax(search) => {
+                *search = Expectimax::new(handicap.depth, search.probability_cutoff());
+                self.noise = handicap.noise.clamp(0.0, 1.0);
+            }
+            _ => self.noise = handicap.noise_without_search(),
+        }
+    }
+
+    pub fn seed_rng(&mut self, seed: u64) {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    pub fn get_next_move(&mut self, state: &BoardState) -> Direction {
+        // 先把策略取出来，策略内部状态和 self 上的评估函数才能同时借用
+        let mut strategy = std::mem::replace(&mut self.current_strategy, Strategy::CornerMax);
+        let direction = match &mut strategy {
             Strategy::CornerMax => self.corner_max_strategy(state),
-            Strategy::SnakePattern => self.snake_pattern_strategy(state),
-            Strategy::MonotonicRows => self.monotonic_rows_strategy(state),
-            Strategy::AdaptiveLearning { weights, last_move, last_state } => {
-                self.adaptive_learning_strategy(state, weights, last_move, last_state)
+            Strategy::Greedy => self.greedy_strategy(state),
+            Strategy::AdaptiveLearning { network, last_afterstate } => {
+                self.adaptive_learning_strategy(state, network, last_afterstate)
             }
+            Strategy::Expectimax(search) => search
+                .best_move(state, |board| self.evaluator.evaluate(board))
+                .unwrap_or(Direction::Left),
+            Strategy::MonteCarlo { rollouts, depth } => {
+                monte_carlo::best_move(state, *rollouts, *depth, &mut self.rng)
+                    .unwrap_or(Direction::Left)
+            }
+        };
+        self.current_strategy = strategy;
+        self.apply_noise(state, direction)
+    }
+
+    // 限时选步：期望最大搜索逐层加深，蒙特卡洛持续模拟，到时间就返回目前最好的方向；
+    // 其他策略本身足够快，直接走 get_next_move
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn get_next_move_within(&mut self, state: &BoardState, budget: Duration) -> Direction {
+        self.get_next_move_with_clock(state, budget, &SystemClock::new())
+    }
+
+    pub fn get_next_move_with_clock(&mut self, state: &BoardState, budget: Duration, clock: &impl Clock) -> Direction {
+        let deadline = clock.now() + budget;
+        let expired = || clock.now() >= deadline;
+
+        let mut strategy = std::mem::replace(&mut self.current_strategy, Strategy::CornerMax);
+        let direction = match &mut strategy {
+            Strategy::Expectimax(search) => {
+                // 逐层加深不超过设定的深度，削弱过的 AI 有再多时间也不会搜得更深
+                let max_depth = search.depth().min(MAX_SEARCH_DEPTH);
+                search.best_move_until(state, |board| self.evaluator.evaluate(board), max_depth, expired)
+            }
+            Strategy::MonteCarlo { depth, .. } => {
+                monte_carlo::best_move_until(state, *depth, &mut self.rng, expired)
+            }
+            _ => None,
+        };
+        self.current_strategy = strategy;
+        match direction {
+            Some(direction) => self.apply_noise(state, direction),
+            None => self.get_next_move(state),
         }
     }
 
-    fn corner_max_strategy(&self, state: &BoardState) -> Direction {
+    // 按 noise 概率把选定的方向换成随机的合法方向；自适应 AI 同时记下实际走出的局面，TD 更新才对得上
+    fn apply_noise(&mut self, state: &BoardState, direction: Direction) -> Direction {
+        if self.noise <= 0.0 || !self.rng.gen_bool(self.noise as f64) {
+            return direction;
+        }
+        let moves: Vec<(Direction, BoardState)> = Direction::ALL
+            .iter()
+            .filter_map(|&d| state.apply_move(d).map(|after| (d, after)))
+            .collect();
+        match moves.choose(&mut self.rng) {
+            Some((random, after)) => {
+                if let Strategy::AdaptiveLearning { last_afterstate, .. } = &mut self.current_strategy {
+                    *last_afterstate = Some(after.clone());
+                }
+                *random
+            }
+            None => direction,
+        }
+    }
+
+    fn corner_max_strategy(&mut self, state: &BoardState) -> Direction {
         // 简单策略：尽量将最大数字保持在角落
-        let corners = [
-            state.grid[0][0],
-            state.grid[0][3],
-            state.grid[3][0],
-            state.grid[3][3],
-        ];
+        let corners = state.corners();
         
         let max_corner = corners.iter().max().unwrap();
         let max_value = state.grid.iter()
@@ -100,268 +289,76 @@ impl AIPlayer {
         } else {
             // 尝试移动到角落
             let directions = [Direction::Left, Direction::Up];
-            directions[rand::thread_rng().gen_range(0..2)]
+            directions[self.rng.gen_range(0..2)]
         }
     }
 
-    fn snake_pattern_strategy(&self, state: &BoardState) -> Direction {
-        // 蛇形模式策略
-        let mut score = HashMap::new();
-        score.insert(Direction::Up, self.evaluate_snake_pattern(state, Direction::Up));
-        score.insert(Direction::Right, self.evaluate_snake_pattern(state, Direction::Right));
-        score.insert(Direction::Down, self.evaluate_snake_pattern(state, Direction::Down));
-        score.insert(Direction::Left, self.evaluate_snake_pattern(state, Direction::Left));
-        
-        score.into_iter()
-            .max_by_key(|&(_, score)| (score * 1000.0) as i32)
-            .map(|(dir, _)| dir)
-            .unwrap_or(Direction::Left)
-    }
-
-    fn evaluate_snake_pattern(&self, state: &BoardState, direction: Direction) -> f32 {
-        let mut score = 0.0;
-        let pattern = match direction {
-            Direction::Left | Direction::Right => {
-                // 横向蛇形
-                vec![
-                    vec![(0,0), (0,1), (0,2), (0,3)],
-                    vec![(1,3), (1,2), (1,1), (1,0)],
-                    vec![(2,0), (2,1), (2,2), (2,3)],
-                    vec![(3,3), (3,2), (3,1), (3,0)],
-                ]
-            },
-            Direction::Up | Direction::Down => {
-                // 纵向蛇形
-                vec![
-                    vec![(0,0), (1,0), (2,0), (3,0)],
-                    vec![(3,1), (2,1), (1,1), (0,1)],
-                    vec![(0,2), (1,2), (2,2), (3,2)],
-                    vec![(3,3), (2,3), (1,3), (0,3)],
-                ]
-            }
-        };
-
-        for (row_idx, row) in pattern.iter().enumerate() {
-            let mut last_value = 0;
-            for &(i, j) in row {
-                let current = state.grid[i][j];
-                if current > last_value {
-                    score += current as f32;
-                } else {
-                    score -= (current as f32) * 0.5;
-                }
-                last_value = current;
-            }
-        }
-
-        score
-    }
-
-    fn monotonic_rows_strategy(&self, state: &BoardState) -> Direction {
-        // 单调行策略：确保每行（或列）都是单调递增或递减的
-        let mut scores = HashMap::new();
-        
-        for direction in [Direction::Up, Direction::Right, Direction::Down, Direction::Left].iter() {
-            let mut score = 0.0;
-            
-            // 评估行的单调性
-            for row in 0..4 {
-                let mut row_score = 0.0;
-                let mut is_increasing = true;
-                let mut is_decreasing = true;
-                
-                for col in 1..4 {
-                    let prev = state.grid[row][col-1];
-                    let curr = state.grid[row][col];
-                    
-                    if curr < prev {
-                        is_increasing = false;
-                    }
-                    if curr > prev {
-                        is_decreasing = false;
-                    }
-                }
-                
-                if is_increasing || is_decreasing {
-                    row_score += state.grid[row].iter().sum::<u32>() as f32;
-                }
-                score += row_score;
-            }
-            
-            scores.insert(*direction, score);
-        }
-        
-        scores.into_iter()
-            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-            .map(|(dir, _)| dir)
+    fn greedy_strategy(&self, state: &BoardState) -> Direction {
+        Direction::ALL.iter()
+            .filter_map(|&direction| {
+                let after = state.apply_move(direction)?;
+                Some((direction, self.evaluator.evaluate(&after)))
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(direction, _)| direction)
             .unwrap_or(Direction::Left)
     }
 
     fn adaptive_learning_strategy(
-        &mut self,
+        &self,
         state: &BoardState,
-        weights: &mut Vec<f32>,
-        last_move: &mut Option<Direction>,
-        last_state: &mut Option<BoardState>
+        network: &mut NTupleNetwork,
+        last_afterstate: &mut Option<BoardState>,
     ) -> Direction {
-        // 自适应学习策略
-        if let (Some(prev_state), Some(prev_move)) = (last_state.clone(), *last_move) {
-            // 计算上一步的奖励
-            let reward = self.calculate_reward(state, &prev_state);
-            
-            // 更新权重
-            self.update_weights(weights, reward, prev_move);
+        // 自适应学习策略：n-tuple 网络估值，对上一步的移动后局面做 TD(0) 更新
+        let choice = network.choose_move(state);
+        if let Some(previous) = last_afterstate.take() {
+            let target = choice.as_ref().map_or(0.0, |(_, next, reward)| reward + network.value(next));
+            network.learn(&previous, target, self.learning_rate);
         }
-        
-        // 使用当前权重评估每个方向
-        let mut best_direction = Direction::Left;
-        let mut best_score = f32::NEG_INFINITY;
-        
-        for direction in [Direction::Up, Direction::Right, Direction::Down, Direction::Left].iter() {
-            let features = self.extract_features(state, *direction);
-            let score = features.iter()
-                .zip(weights.iter())
-                .map(|(f, w)| f * w)
-                .sum::<f32>();
-                
-            if score > best_score {
-                best_score = score;
-                best_direction = *direction;
+
+        match choice {
+            Some((direction, afterstate, _)) => {
+                *last_afterstate = Some(afterstate);
+                direction
             }
+            None => Direction::Left,
         }
-        
-        // 更新状态
-        *last_state = Some(state.clone());
-        *last_move = Some(best_direction);
-        
-        best_direction
     }
 
-    fn calculate_reward(&self, current: &BoardState, previous: &BoardState) -> f32 {
-        let current_max = current.grid.iter()
-            .flat_map(|row| row.iter())
-            .max()
-            .unwrap();
-            
-        let previous_max = previous.grid.iter()
-            .flat_map(|row| row.iter())
-            .max()
-            .unwrap();
-            
-        let score_diff = current.score as f32 - previous.score as f32;
-        let max_diff = (*current_max as f32 - *previous_max as f32) * 2.0;
-        
-        score_diff + max_diff
-    }
-
-    fn update_weights(&mut self, weights: &mut Vec<f32>, reward: f32, direction: Direction) {
-        let direction_idx = match direction {
-            Direction::Up => 0,
-            Direction::Right => 1,
-            Direction::Down => 2,
-            Direction::Left => 3,
-        };
-        
-        weights[direction_idx] += self.learning_rate * reward;
-        
-        // 归一化权重
-        let sum: f32 = weights.iter().sum();
-        for w in weights.iter_mut() {
-            *w /= sum;
+    // 一局结束时调用，把最后一步的移动后局面向 0 更新
+    pub fn finish_game(&mut self) {
+        let learning_rate = self.learning_rate;
+        if let Strategy::AdaptiveLearning { network, last_afterstate } = &mut self.current_strategy {
+            if let Some(previous) = last_afterstate.take() {
+                network.learn(&previous, 0.0, learning_rate);
+            }
         }
     }
 
-    fn extract_features(&self, state: &BoardState, direction: Direction) -> Vec<f32> {
-        vec![
-            self.empty_cells_after_move(state, direction),
-            self.merge_potential(state, direction),
-            self.edge_value(state, direction),
-            self.monotonicity(state, direction),
-        ]
-    }
-
-    fn empty_cells_after_move(&self, state: &BoardState, _direction: Direction) -> f32 {
-        state.grid.iter()
-            .flat_map(|row| row.iter())
-            .filter(|&&cell| cell == 0)
-            .count() as f32 / 16.0
-    }
-
-    fn merge_potential(&self, state: &BoardState, _direction: Direction) -> f32 {
-        let mut potential = 0;
-        
-        // 检查行
-        for row in state.grid.iter() {
-            for i in 0..3 {
-                if row[i] != 0 && row[i] == row[i+1] {
-                    potential += 1;
-                }
-            }
-        }
-        
-        // 检查列
-        for col in 0..4 {
-            for row in 0..3 {
-                if state.grid[row][col] != 0 && state.grid[row][col] == state.grid[row+1][col] {
-                    potential += 1;
-                }
-            }
+    // 离线自我对弈训练自适应 AI 的网络
+    pub fn train(&mut self, games: u32, rows: usize, cols: usize) {
+        let learning_rate = self.learning_rate;
+        if let Strategy::AdaptiveLearning { network, .. } = &mut self.current_strategy {
+            network.train(games, rows, cols, learning_rate, &mut self.rng);
         }
-        
-        potential as f32 / 24.0 // 最大可能的合并数
     }
 
-    fn edge_value(&self, state: &BoardState, _direction: Direction) -> f32 {
-        let edge_sum = state.grid[0][0] + state.grid[0][3] + state.grid[3][0] + state.grid[3][3];
-        let max_value = state.grid.iter()
-            .flat_map(|row| row.iter())
-            .max()
-            .unwrap();
-            
-        edge_sum as f32 / (*max_value as f32 * 4.0)
+    pub fn save_network(&self) -> Option<Vec<u8>> {
+        match &self.current_strategy {
+            Strategy::AdaptiveLearning { network, .. } => Some(network.to_bytes()),
+            _ => None,
+        }
     }
 
-    fn monotonicity(&self, state: &BoardState, _direction: Direction) -> f32 {
-        let mut score = 0;
-        
-        // 检查行的单调性
-        for row in state.grid.iter() {
-            let mut is_increasing = true;
-            let mut is_decreasing = true;
-            
-            for i in 1..4 {
-                if row[i] < row[i-1] {
-                    is_increasing = false;
-                }
-                if row[i] > row[i-1] {
-                    is_decreasing = false;
-                }
-            }
-            
-            if is_increasing || is_decreasing {
-                score += 1;
-            }
-        }
-        
-        // 检查列的单调性
-        for col in 0..4 {
-            let mut is_increasing = true;
-            let mut is_decreasing = true;
-            
-            for row in 1..4 {
-                if state.grid[row][col] < state.grid[row-1][col] {
-                    is_increasing = false;
-                }
-                if state.grid[row][col] > state.grid[row-1][col] {
-                    is_decreasing = false;
-                }
-            }
-            
-            if is_increasing || is_decreasing {
-                score += 1;
+    pub fn load_network(&mut self, bytes: &[u8]) -> Result<(), String> {
+        match &mut self.current_strategy {
+            Strategy::AdaptiveLearning { network, last_afterstate } => {
+                *network = NTupleNetwork::from_bytes(bytes)?;
+                *last_afterstate = None;
+                Ok(())
             }
+            _ => Err("Only the adaptive AI has a learned network".to_string()),
         }
-        
-        score as f32 / 8.0 // 最大可能的单调行/列数
     }
-} 
\ No newline at end of file
+}
\ No newline at end of file
@@ -0,0 +1,82 @@
+use serde::{Serialize, Deserialize};
+
+use super::{monte_carlo, AIPlayer, BoardState, Direction, Strategy};
+
+// 单个方向的分析结果，不合法的方向没有期望值
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DirectionAnalysis {
+    pub direction: Direction,
+    pub legal: bool,
+    pub expected_score: Option<f32>,
+}
+
+// 提示按钮和赛后复盘用的局面分析，confidence 在 0 到 1 之间，
+// 只有一个合法方向时为 1，最优和次优方向难分高下时接近 0
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MoveAnalysis {
+    pub directions: Vec<DirectionAnalysis>,
+    pub recommended: Option<Direction>,
+    pub confidence: f32,
+}
+
+impl AIPlayer {
+    // 用当前策略的评估函数给每个方向打分，grid 为方块数值（0 表示空格）
+    pub fn analyze(&mut self, grid: Vec<Vec<u32>>, score: u32) -> MoveAnalysis {
+        let state = BoardState { grid, score };
+        let values = self.evaluate_moves(&state);
+
+        let mut ranked: Vec<(Direction, f32)> = values.iter()
+            .filter_map(|&(direction, value)| value.map(|v| (direction, v)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let confidence = match ranked.as_slice() {
+            [] => 0.0,
+            [_] => 1.0,
+            [(_, best), (_, second), ..] => {
+                let scale = best.abs().max(second.abs()).max(f32::EPSILON);
+                ((best - second) / scale).clamp(0.0, 1.0)
+            }
+        };
+
+        MoveAnalysis {
+            directions: values.into_iter()
+                .map(|(direction, value)| DirectionAnalysis {
+                    direction,
+                    legal: value.is_some(),
+                    expected_score: value,
+                })
+                .collect(),
+            recommended: ranked.first().map(|&(direction, _)| direction),
+            confidence,
+        }
+    }
+
+    // 每个方向的估值：搜索类策略用各自的搜索结果，其余策略看一步后的局面评估
+    fn evaluate_moves(&mut self, state: &BoardState) -> Vec<(Direction, Option<f32>)> {
+        let mut strategy = std::mem::replace(&mut self.current_strategy, Strategy::CornerMax);
+        let values = match &mut strategy {
+            Strategy::Expectimax(search) => {
+                search.evaluate_moves(state, &|board: &BoardState| self.evaluator.evaluate(board))
+            }
+            Strategy::MonteCarlo { rollouts, depth } => {
+                monte_carlo::evaluate_moves(state, *rollouts, *depth, &mut self.rng)
+            }
+            Strategy::AdaptiveLearning { network, .. } => {
+                one_ply(state, |after| (after.score - state.score) as f32 + network.value(after))
+            }
+            _ => one_ply(state, |after| self.evaluator.evaluate(after)),
+        };
+        self.current_strategy = strategy;
+        values
+    }
+}
+
+fn one_ply(state: &BoardState, evaluate: impl Fn(&BoardState) -> f32) -> Vec<(Direction, Option<f32>)> {
+    Direction::ALL.iter()
+        .map(|&direction| {
+            let value = state.apply_move(direction).map(|after| evaluate(&after));
+            (direction, value)
+        })
+        .collect()
+}
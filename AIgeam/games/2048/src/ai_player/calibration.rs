@@ -0,0 +1,164 @@
+use serde::{Serialize, Deserialize};
+use std::collections::VecDeque;
+
+use super::persistence::ByteReader;
+use super::AIPlayer;
+
+// 参与估计玩家水平的最近对局数
+const HISTORY_WINDOW: usize = 20;
+
+// 每局结束后的强度调整量：AI 得分与目标每差一倍调整 0.1
+const ADJUST_RATE: f32 = 0.1;
+// 达到这个速度的玩家在速度一项上拿满分
+const FAST_MOVES_PER_SECOND: f32 = 4.0;
+// 强度为 1 时的搜索深度，强度为 0 时为 1 层
+const MAX_HANDICAP_DEPTH: u32 = 3;
+// 没有搜索深度的策略每少一层折算成的额外随机走子概率
+const DEPTH_NOISE: f32 = 0.1;
+
+// 玩家一局的结果，error_rate 和 move_speed 取自 GameStats（move_speed 为每秒有效移动次数）
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct PlayerGame {
+    pub score: u32,
+    pub max_tile: u32,
+    pub error_rate: f32,
+    pub move_speed: f32,
+}
+
+// 让 AI 变弱的两个手段：搜索深度和随机走子的概率
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Handicap {
+    pub depth: u32,
+    pub noise: f32,
+}
+
+impl Handicap {
+    // 贪心、自适应网络等策略没有搜索深度可调，把比满深度少的层数折算成额外的随机走子概率，
+    // 这样强度在所有难度上都能覆盖同样的范围
+    pub fn noise_without_search(&self) -> f32 {
+        let missing = MAX_HANDICAP_DEPTH.saturating_sub(self.depth) as f32;
+        (self.noise + DEPTH_NOISE * missing).clamp(0.0, 1.0)
+    }
+}
+
+// 对战模式的难度校准：根据玩家历史估计其水平（以 log2 得分衡量），
+// 选择 AI 的强度使其预期得分落在玩家水平上下 band 的范围内，每局结束后根据实际结果修正
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SkillCalibrator {
+    history: VecDeque<PlayerGame>,
+    strength: f32,
+    band: f32,
+}
+
+impl SkillCalibrator {
+    pub fn new(band: f32) -> Self {
+        Self {
+            history: VecDeque::with_capacity(HISTORY_WINDOW),
+            strength: 0.5,
+            band: band.max(0.0),
+        }
+    }
+
+    // 从已有的玩家历史开始：第一局对战就以历史估计的玩家水平为目标，
+    // 强度仍从中间值出发，按每局 AI 得分与目标的差距修正
+    pub fn from_history(games: impl IntoIterator<Item = PlayerGame>, band: f32) -> Self {
+        let mut calibrator = Self::new(band);
+        for game in games {
+            calibrator.push_game(game);
+        }
+        calibrator
+    }
+
+    // 玩家水平：得分和最大方块两个角度估计的 log2 得分取平均，
+    // 失误率高的玩家往往上不去，按失误率下调；操作速度只作小幅修正
+    pub fn player_level(&self) -> Option<f32> {
+        if self.history.is_empty() {
+            return None;
+        }
+        let total: f32 = self.history.iter()
+            .map(|game| {
+                let from_score = (game.score.max(1) as f32).log2();
+                let tile = (game.max_tile.max(2) as f32).log2();
+                // 合成出 2^k 大约需要 (k - 1) * 2^k 分
+                let from_tile = tile + (tile - 1.0).max(1.0).log2();
+                let base = (from_score + from_tile) / 2.0;
+                let speed = (game.move_speed / FAST_MOVES_PER_SECOND).clamp(0.0, 1.0);
+                base - game.error_rate.clamp(0.0, 1.0) + 0.5 * (speed - 0.5)
+            })
+            .sum();
+        Some(total / self.history.len() as f32)
+    }
+
+    pub fn strength(&self) -> f32 {
+        self.strength
+    }
+
+    // 一局对战结束：记录玩家结果，AI 得分超出目标范围时按差距调整强度
+    pub fn record_game(&mut self, player: PlayerGame, ai_score: u32) {
+        self.push_game(player);
+        let target = match self.player_level() {
+            Some(level) => level,
+            None => return,
+        };
+        let gap = target - (ai_score.max(1) as f32).log2();
+        if gap.abs() > self.band {
+            self.strength = (self.strength + ADJUST_RATE * gap).clamp(0.0, 1.0);
+        }
+    }
+
+    pub fn handicap(&self) -> Handicap {
+        Handicap {
+            depth: 1 + (self.strength * (MAX_HANDICAP_DEPTH - 1) as f32).round() as u32,
+            noise: 0.6 * (1.0 - self.strength),
+        }
+    }
+
+    pub fn apply(&self, player: &mut AIPlayer) {
+        player.set_handicap(self.handicap());
+    }
+
+    // 紧凑的二进制格式：强度、band、对局数，然后每局的得分、最大方块、失误率和速度（均为小端 4 字节）
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&self.strength.to_le_bytes());
+        bytes.extend_from_slice(&self.band.to_le_bytes());
+        bytes.push(self.history.len() as u8);
+        for game in &self.history {
+            bytes.extend_from_slice(&game.score.to_le_bytes());
+            bytes.extend_from_slice(&game.max_tile.to_le_bytes());
+            bytes.extend_from_slice(&game.error_rate.to_le_bytes());
+            bytes.extend_from_slice(&game.move_speed.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = ByteReader::new(bytes);
+        let strength = f32::from_le_bytes(reader.take_array()?);
+        let band = f32::from_le_bytes(reader.take_array()?);
+        if !(0.0..=1.0).contains(&strength) || !band.is_finite() {
+            return Err("Invalid calibration state".to_string());
+        }
+        let mut calibrator = Self::new(band);
+        calibrator.strength = strength;
+        for _ in 0..reader.take(1)?[0] {
+            calibrator.push_game(PlayerGame {
+                score: u32::from_le_bytes(reader.take_array()?),
+                max_tile: u32::from_le_bytes(reader.take_array()?),
+                error_rate: f32::from_le_bytes(reader.take_array()?),
+                move_speed: f32::from_le_bytes(reader.take_array()?),
+            });
+        }
+        if !reader.is_empty() {
+            return Err("Trailing bytes after calibration state".to_string());
+        }
+        Ok(calibrator)
+    }
+
+    fn push_game(&mut self, game: PlayerGame) {
+        if self.history.len() == HISTORY_WINDOW {
+            self.history.pop_front();
+        }
+        self.history.push_back(game);
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use super::{BoardState, NTupleNetwork};
+
+// 局面评估函数，统一在移动后的棋盘上打分，分数越高越好
+// 搜索策略（贪心、期望最大搜索）可以搭配任意评估函数
+pub trait BoardEvaluator: Send + Sync {
+    fn evaluate(&self, board: &BoardState) -> f32;
+}
+
+// 空格比例
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EmptyCells;
+
+impl BoardEvaluator for EmptyCells {
+    fn evaluate(&self, board: &BoardState) -> f32 {
+        board.empty_cells().len() as f32 / board.cell_count() as f32
+    }
+}
+
+// 单调的行/列占全部行列的比例
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Monotonicity;
+
+impl BoardEvaluator for Monotonicity {
+    fn evaluate(&self, board: &BoardState) -> f32 {
+        let (rows, cols) = (board.rows(), board.cols());
+        let monotonic = |line: Vec<u32>| {
+            line.windows(2).all(|w| w[0] <= w[1]) || line.windows(2).all(|w| w[0] >= w[1])
+        };
+
+        let monotonic_rows = (0..rows)
+            .filter(|&i| monotonic(board.grid[i].clone()))
+            .count();
+        let monotonic_cols = (0..cols)
+            .filter(|&j| monotonic((0..rows).map(|i| board.grid[i][j]).collect()))
+            .count();
+
+        (monotonic_rows + monotonic_cols) as f32 / (rows + cols) as f32
+    }
+}
+
+// 相邻且相等的方块对占所有相邻对的比例
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MergePotential;
+
+impl BoardEvaluator for MergePotential {
+    fn evaluate(&self, board: &BoardState) -> f32 {
+        let (rows, cols) = (board.rows(), board.cols());
+        let mut potential = 0;
+
+        for i in 0..rows {
+            for j in 0..cols {
+                let value = board.grid[i][j];
+                if value == 0 {
+                    continue;
+                }
+                if j + 1 < cols && board.grid[i][j + 1] == value {
+                    potential += 1;
+                }
+                if i + 1 < rows && board.grid[i + 1][j] == value {
+                    potential += 1;
+                }
+            }
+        }
+
+        potential as f32 / (rows * (cols - 1) + cols * (rows - 1)) as f32
+    }
+}
+
+// 四个角上的方块之和相对最大方块的比例，鼓励大方块靠角
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EdgeValue;
+
+impl BoardEvaluator for EdgeValue {
+    fn evaluate(&self, board: &BoardState) -> f32 {
+        let max_value = board.grid.iter().flatten().copied().max().unwrap_or(0);
+        if max_value == 0 {
+            return 0.0;
+        }
+        let edge_sum: u32 = board.corners().iter().sum();
+        edge_sum as f32 / (max_value as f32 * 4.0)
+    }
+}
+
+// 蛇形路线：沿蛇形顺序递增加分、下降扣分，横向和纵向两种路线取较好的一种，按方块总和归一化
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SnakePattern;
+
+impl BoardEvaluator for SnakePattern {
+    fn evaluate(&self, board: &BoardState) -> f32 {
+        let (rows, cols) = (board.rows(), board.cols());
+        let horizontal: Vec<Vec<(usize, usize)>> = (0..rows)
+            .map(|i| {
+                let line: Vec<_> = (0..cols).map(|j| (i, j)).collect();
+                if i % 2 == 0 { line } else { line.into_iter().rev().collect() }
+            })
+            .collect();
+        let vertical: Vec<Vec<(usize, usize)>> = (0..cols)
+            .map(|j| {
+                let line: Vec<_> = (0..rows).map(|i| (i, j)).collect();
+                if j % 2 == 0 { line } else { line.into_iter().rev().collect() }
+            })
+            .collect();
+
+        let score = |pattern: &[Vec<(usize, usize)>]| {
+            let mut score = 0.0;
+            for line in pattern {
+                let mut last_value = 0;
+                for &(i, j) in line {
+                    let current = board.grid[i][j];
+                    if current > last_value {
+                        score += current as f32;
+                    } else {
+                        score -= current as f32 * 0.5;
+                    }
+                    last_value = current;
+                }
+            }
+            score
+        };
+
+        let total: u32 = board.grid.iter().flatten().sum();
+        if total == 0 {
+            return 0.0;
+        }
+        score(&horizontal).max(score(&vertical)) / total as f32
+    }
+}
+
+impl BoardEvaluator for NTupleNetwork {
+    fn evaluate(&self, board: &BoardState) -> f32 {
+        self.value(board)
+    }
+}
+
+// 多个评估函数的加权和
+#[derive(Clone, Default)]
+pub struct WeightedEvaluator {
+    terms: Vec<(f32, Arc<dyn BoardEvaluator>)>,
+}
+
+impl WeightedEvaluator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, weight: f32, evaluator: impl BoardEvaluator + 'static) -> Self {
+        self.terms.push((weight, Arc::new(evaluator)));
+        self
+    }
+}
+
+impl BoardEvaluator for WeightedEvaluator {
+    fn evaluate(&self, board: &BoardState) -> f32 {
+        self.terms.iter()
+            .map(|(weight, evaluator)| weight * evaluator.evaluate(board))
+            .sum()
+    }
+}
+
+// 期望最大搜索默认使用的组合：空格、单调性、可合并数和角落方块
+pub fn default_evaluator() -> Arc<dyn BoardEvaluator> {
+    Arc::new(
+        WeightedEvaluator::new()
+            .with(2.7, EmptyCells)
+            .with(1.0, Monotonicity)
+            .with(1.0, MergePotential)
+            .with(0.5, EdgeValue),
+    )
+}
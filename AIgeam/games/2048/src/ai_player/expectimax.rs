@@ -0,0 +1,186 @@
+use serde::{Serialize, Deserialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use super::{BoardState, Direction};
+
+// 新方块为 2 和 4 的概率
+const SPAWN_PROBABILITIES: [(u32, f32); 2] = [(2, 0.9), (4, 0.1)];
+
+// 无路可走（输掉）的局面的分值，远低于任何评估函数给出的分数；
+// 用有限值而不是负无穷，期望值里仍能比较不同走法的死局概率
+const LOSS_VALUE: f32 = -1.0e9;
+
+// 期望最大搜索：玩家节点取最大值，随机生成方块的节点取期望值
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Expectimax {
+    depth: u32,
+    probability_cutoff: f32,
+    #[serde(skip)]
+    table: HashMap<u64, CachedValue>,
+    // 限时搜索中途超时的标记，超时后的结果不写入置换表也不采用
+    #[serde(skip)]
+    aborted: bool,
+}
+
+#[derive(Clone, Copy)]
+struct CachedValue {
+    depth: u32,
+    value: f32,
+}
+
+impl Expectimax {
+    pub fn new(depth: u32, probability_cutoff: f32) -> Self {
+        Self {
+            depth: depth.max(1),
+            probability_cutoff,
+            table: HashMap::new(),
+            aborted: false,
+        }
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    pub fn probability_cutoff(&self) -> f32 {
+        self.probability_cutoff
+    }
+
+    // 返回期望值最高的方向，没有任何合法移动时返回 None
+    pub fn best_move(&mut self, state: &BoardState, evaluate: impl Fn(&BoardState) -> f32) -> Option<Direction> {
+        best_of(&self.evaluate_moves(state, &evaluate))
+    }
+
+    // 每个方向的期望值，不合法的方向为 None
+    pub fn evaluate_moves(
+        &mut self,
+        state: &BoardState,
+        evaluate: &impl Fn(&BoardState) -> f32,
+    ) -> Vec<(Direction, Option<f32>)> {
+        // 置换表只在一次搜索内有效，避免不同深度的结果互相污染
+        self.table.clear();
+        self.search(state, self.depth, evaluate, &|| false)
+            .expect("search without a deadline never aborts")
+    }
+
+    // 迭代加深：从 1 层开始逐层加深，超时则放弃正在进行的这一层，返回已完成的最深一层的最佳方向
+    // 第 1 层总会完整算完，保证至少有一个结果
+    pub fn best_move_until(
+        &mut self,
+        state: &BoardState,
+        evaluate: impl Fn(&BoardState) -> f32,
+        max_depth: u32,
+        expired: impl Fn() -> bool,
+    ) -> Option<Direction> {
+        self.table.clear();
+        let mut best = None;
+        for depth in 1..=max_depth.max(1) {
+            let stop: &dyn Fn() -> bool = if depth == 1 { &|| false } else { &expired };
+            match self.search(state, depth, &evaluate, stop) {
+                Some(values) => best = best_of(&values).or(best),
+                None => break,
+            }
+            if expired() {
+                break;
+            }
+        }
+        best
+    }
+
+    fn search(
+        &mut self,
+        state: &BoardState,
+        depth: u32,
+        evaluate: &impl Fn(&BoardState) -> f32,
+        stop: &dyn Fn() -> bool,
+    ) -> Option<Vec<(Direction, Option<f32>)>> {
+        self.aborted = false;
+        let values = Direction::ALL.iter()
+            .map(|&direction| {
+                let value = state.apply_move(direction)
+                    .map(|next| self.chance_node(&next, depth - 1, 1.0, evaluate, stop));
+                (direction, value)
+            })
+            .collect();
+        if self.aborted { None } else { Some(values) }
+    }
+
+    fn max_node(
+        &mut self,
+        state: &BoardState,
+        depth: u32,
+        probability: f32,
+        evaluate: &impl Fn(&BoardState) -> f32,
+        stop: &dyn Fn() -> bool,
+    ) -> f32 {
+        let mut best = None;
+        for &direction in Direction::ALL.iter() {
+            if let Some(next) = state.apply_move(direction) {
+                let value = self.chance_node(&next, depth, probability, evaluate, stop);
+                best = Some(best.map_or(value, |b: f32| b.max(value)));
+            }
+        }
+        // 无路可走的局面直接给出最低分
+        best.unwrap_or(LOSS_VALUE)
+    }
+
+    fn chance_node(
+        &mut self,
+        state: &BoardState,
+        depth: u32,
+        probability: f32,
+        evaluate: &impl Fn(&BoardState) -> f32,
+        stop: &dyn Fn() -> bool,
+    ) -> f32 {
+        if depth == 0 || probability < self.probability_cutoff {
+            return evaluate(state);
+        }
+        if self.aborted || stop() {
+            self.aborted = true;
+            return 0.0;
+        }
+
+        let key = board_hash(state);
+        if let Some(cached) = self.table.get(&key) {
+            if cached.depth >= depth {
+                return cached.value;
+            }
+        }
+
+        let empty = state.empty_cells();
+        if empty.is_empty() {
+            return evaluate(state);
+        }
+
+        let cell_probability = 1.0 / empty.len() as f32;
+        let mut expected = 0.0;
+        for &(i, j) in &empty {
+            for &(value, spawn_probability) in SPAWN_PROBABILITIES.iter() {
+                let next = state.with_tile(i, j, value);
+                let p = cell_probability * spawn_probability;
+                expected += p * self.max_node(&next, depth - 1, probability * p, evaluate, stop);
+            }
+        }
+
+        if self.aborted {
+            return 0.0;
+        }
+        self.table.insert(key, CachedValue { depth, value: expected });
+        expected
+    }
+}
+
+fn best_of(values: &[(Direction, Option<f32>)]) -> Option<Direction> {
+    values.iter()
+        .filter_map(|&(direction, value)| value.map(|v| (direction, v)))
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(direction, _)| direction)
+}
+
+fn board_hash(state: &BoardState) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.grid.hash(&mut hasher);
+    hasher.finish()
+}
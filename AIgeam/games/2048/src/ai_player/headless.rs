@@ -0,0 +1,57 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Serialize, Deserialize};
+
+use super::monte_carlo::spawn_random_tile;
+use super::{AIPlayer, BoardState, Direction};
+
+// 一局无界面对局的结果，供评测工具汇总
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HeadlessGame {
+    pub seed: u64,
+    pub score: u32,
+    pub max_tile: u32,
+    pub moves: u32,
+    pub illegal_moves: u32,
+    pub won: bool,
+}
+
+impl AIPlayer {
+    // 用固定种子完整下一局，AI 给出不合法方向时记一次并按固定顺序换成第一个合法方向
+    pub fn play_headless(&mut self, seed: u64, rows: usize, cols: usize, win_tile: u32) -> HeadlessGame {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let empty = BoardState { grid: vec![vec![0; cols]; rows], score: 0 };
+        let mut state = spawn_random_tile(&spawn_random_tile(&empty, &mut rng), &mut rng);
+        let mut moves = 0;
+        let mut illegal_moves = 0;
+
+        loop {
+            let direction = self.get_next_move(&state);
+            let next = match state.apply_move(direction) {
+                Some(next) => next,
+                None => {
+                    match Direction::ALL.iter().find_map(|&d| state.apply_move(d)) {
+                        Some(next) => {
+                            illegal_moves += 1;
+                            next
+                        }
+                        None => break,
+                    }
+                }
+            };
+            state = spawn_random_tile(&next, &mut rng);
+            moves += 1;
+        }
+        self.finish_game();
+
+        let max_tile = state.grid.iter().flatten().copied().max().unwrap_or(0);
+        HeadlessGame {
+            seed,
+            score: state.score,
+            max_tile,
+            moves,
+            illegal_moves,
+            won: max_tile >= win_tile,
+        }
+    }
+}
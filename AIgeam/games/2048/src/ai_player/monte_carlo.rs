@@ -0,0 +1,86 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+use game_2048_core::CLASSIC_SPAWN_WEIGHTS;
+
+use super::{BoardState, Direction};
+
+// 随机生成一个新方块（90% 为 2，10% 为 4），棋盘已满时原样返回
+pub(super) fn spawn_random_tile(state: &BoardState, rng: &mut impl Rng) -> BoardState {
+    match game_2048_core::choose_spawn(&state.empty_cells(), &CLASSIC_SPAWN_WEIGHTS, rng) {
+        Some((i, j, exponent)) => state.with_tile(i, j, game_2048_core::exponent_to_value(exponent)),
+        None => state.clone(),
+    }
+}
+
+// 从移动后的局面开始随机走到底（最多 depth 步），返回这一局额外获得的分数
+fn rollout(after_move: &BoardState, depth: u32, rng: &mut impl Rng) -> f32 {
+    let mut state = spawn_random_tile(after_move, rng);
+    for _ in 0..depth {
+        let moves: Vec<BoardState> = Direction::ALL.iter()
+            .filter_map(|&direction| state.apply_move(direction))
+            .collect();
+        match moves.choose(rng) {
+            Some(next) => state = spawn_random_tile(next, rng),
+            None => break,
+        }
+    }
+    (state.score - after_move.score) as f32
+}
+
+// 每个合法方向做 rollouts 次随机对局，取平均得分（含这一步本身的得分）
+pub(super) fn evaluate_moves(
+    state: &BoardState,
+    rollouts: u32,
+    depth: u32,
+    rng: &mut impl Rng,
+) -> Vec<(Direction, Option<f32>)> {
+    Direction::ALL.iter()
+        .map(|&direction| {
+            let value = state.apply_move(direction).map(|next| {
+                let immediate = (next.score - state.score) as f32;
+                let total: f32 = (0..rollouts.max(1)).map(|_| rollout(&next, depth, rng)).sum();
+                immediate + total / rollouts.max(1) as f32
+            });
+            (direction, value)
+        })
+        .collect()
+}
+
+pub(super) fn best_move(state: &BoardState, rollouts: u32, depth: u32, rng: &mut impl Rng) -> Option<Direction> {
+    evaluate_moves(state, rollouts, depth, rng)
+        .into_iter()
+        .filter_map(|(direction, value)| value.map(|v| (direction, v)))
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(direction, _)| direction)
+}
+
+// 限时版本：每轮给每个合法方向各做一次随机对局，直到超时（至少一轮），取平均分最高的方向
+pub(super) fn best_move_until(
+    state: &BoardState,
+    depth: u32,
+    rng: &mut impl Rng,
+    expired: impl Fn() -> bool,
+) -> Option<Direction> {
+    let mut candidates: Vec<(Direction, BoardState, f32)> = Direction::ALL.iter()
+        .filter_map(|&direction| state.apply_move(direction).map(|next| (direction, next, 0.0)))
+        .collect();
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let mut rounds = 0;
+    loop {
+        for (_, next, total) in candidates.iter_mut() {
+            *total += rollout(next, depth, rng);
+        }
+        rounds += 1;
+        if expired() {
+            break;
+        }
+    }
+
+    candidates.into_iter()
+        .map(|(direction, next, total)| (direction, (next.score - state.score) as f32 + total / rounds as f32))
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(direction, _)| direction)
+}
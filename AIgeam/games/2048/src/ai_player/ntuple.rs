@@ -0,0 +1,193 @@
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use super::monte_carlo::spawn_random_tile;
+use super::persistence::ByteReader;
+use super::{BoardState, Direction};
+
+// 每格的指数截断到 15，查找表按 16 进制编码
+const EXPONENT_RANGE: usize = 16;
+// 序列化后一个组合至少占用的字节数：长度、一个格子的坐标和 16 项的查找表
+const MIN_TUPLE_BYTES: usize = 1 + 2 + EXPONENT_RANGE * 4;
+
+// n-tuple 网络：若干个格子组合（tuple），每个组合对应一张查找表，
+// 局面估值为所有组合（含对称变换）查表值之和
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NTupleNetwork {
+    tuples: Vec<Vec<(usize, usize)>>,
+    weights: Vec<Vec<f32>>,
+}
+
+impl NTupleNetwork {
+    pub fn new(tuples: Vec<Vec<(usize, usize)>>) -> Self {
+        let weights = tuples.iter()
+            .map(|tuple| vec![0.0; EXPONENT_RANGE.pow(tuple.len() as u32)])
+            .collect();
+        Self { tuples, weights }
+    }
+
+    // 两条直线 + 两个 2x2 方块，配合 8 种对称变换覆盖整个 4x4 棋盘
+    pub fn with_default_tuples() -> Self {
+        Self::new(vec![
+            vec![(0, 0), (0, 1), (0, 2), (0, 3)],
+            vec![(1, 0), (1, 1), (1, 2), (1, 3)],
+            vec![(0, 0), (0, 1), (1, 0), (1, 1)],
+            vec![(1, 0), (1, 1), (2, 0), (2, 1)],
+        ])
+    }
+
+    // 列出局面命中的所有 (查找表, 下标)，超出棋盘范围的组合会被跳过
+    fn features(&self, state: &BoardState) -> Vec<(usize, usize)> {
+        let (rows, cols) = (state.rows(), state.cols());
+        let symmetries = if rows == cols { 8 } else { 4 };
+        let mut features = Vec::with_capacity(self.tuples.len() * symmetries);
+
+        for (t, tuple) in self.tuples.iter().enumerate() {
+            for symmetry in 0..symmetries {
+                let mut index = 0;
+                let mut inside = true;
+                for &(r, c) in tuple.iter().rev() {
+                    match transform(r, c, rows, cols, symmetry) {
+                        Some((i, j)) => index = index * EXPONENT_RANGE + exponent(state.grid[i][j]),
+                        None => {
+                            inside = false;
+                            break;
+                        }
+                    }
+                }
+                if inside {
+                    features.push((t, index));
+                }
+            }
+        }
+        features
+    }
+
+    pub(super) fn value(&self, state: &BoardState) -> f32 {
+        self.features(state)
+            .into_iter()
+            .map(|(t, index)| self.weights[t][index])
+            .sum()
+    }
+
+    // TD 更新：把 state 的估值向 target 靠拢，学习率按命中的表项数平均分配
+    pub(super) fn learn(&mut self, state: &BoardState, target: f32, learning_rate: f32) {
+        let features = self.features(state);
+        if features.is_empty() {
+            return;
+        }
+        let delta = target - self.value(state);
+        let step = learning_rate * delta / features.len() as f32;
+        for (t, index) in features {
+            self.weights[t][index] += step;
+        }
+    }
+
+    // 选择 即时得分 + 移动后局面估值 最大的方向，返回方向、移动后局面和得分
+    pub(super) fn choose_move(&self, state: &BoardState) -> Option<(Direction, BoardState, f32)> {
+        Direction::ALL.iter()
+            .filter_map(|&direction| {
+                let after = state.apply_move(direction)?;
+                let reward = (after.score - state.score) as f32;
+                Some((direction, after, reward))
+            })
+            .max_by(|a, b| {
+                let va = a.2 + self.value(&a.1);
+                let vb = b.2 + self.value(&b.1);
+                va.partial_cmp(&vb).unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+
+    // 自我对弈训练若干局，使用移动后局面（afterstate）上的 TD(0) 学习
+    pub fn train(&mut self, games: u32, rows: usize, cols: usize, learning_rate: f32, rng: &mut impl Rng) {
+        for _ in 0..games {
+            let empty = BoardState { grid: vec![vec![0; cols]; rows], score: 0 };
+            let mut state = spawn_random_tile(&spawn_random_tile(&empty, rng), rng);
+            let mut previous: Option<BoardState> = None;
+
+            loop {
+                let choice = self.choose_move(&state);
+                if let Some(after) = previous.take() {
+                    let target = choice.as_ref().map_or(0.0, |(_, next, reward)| reward + self.value(next));
+                    self.learn(&after, target, learning_rate);
+                }
+                match choice {
+                    Some((_, after, _)) => {
+                        state = spawn_random_tile(&after, rng);
+                        previous = Some(after);
+                    }
+                    None => break,
+                }
+            }
+        }
+    }
+
+    // 紧凑的二进制格式：组合数，然后每个组合的格子坐标和小端 f32 查找表
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(self.tuples.len() as u32).to_le_bytes());
+        for (tuple, weights) in self.tuples.iter().zip(self.weights.iter()) {
+            bytes.push(tuple.len() as u8);
+            for &(r, c) in tuple {
+                bytes.push(r as u8);
+                bytes.push(c as u8);
+            }
+            for weight in weights {
+                bytes.extend_from_slice(&weight.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = ByteReader::new(bytes);
+        let count = u32::from_le_bytes(reader.take_array()?) as usize;
+        // 组合数来自外部数据，预分配的容量不能超过剩余字节最多能容纳的组合数
+        let capacity = count.min(reader.remaining() / MIN_TUPLE_BYTES);
+        let mut tuples = Vec::with_capacity(capacity);
+        let mut weights = Vec::with_capacity(capacity);
+
+        for _ in 0..count {
+            let len = reader.take(1)?[0] as usize;
+            if len == 0 || len > 6 {
+                return Err(format!("Invalid tuple length {}", len));
+            }
+            let tuple: Vec<(usize, usize)> = reader.take(len * 2)?
+                .chunks(2)
+                .map(|pair| (pair[0] as usize, pair[1] as usize))
+                .collect();
+            let table = (0..EXPONENT_RANGE.pow(len as u32))
+                .map(|_| reader.take_array().map(f32::from_le_bytes))
+                .collect::<Result<Vec<f32>, String>>()?;
+            tuples.push(tuple);
+            weights.push(table);
+        }
+
+        if !reader.is_empty() {
+            return Err("Trailing bytes after n-tuple tables".to_string());
+        }
+        Ok(Self { tuples, weights })
+    }
+}
+
+fn exponent(value: u32) -> usize {
+    if value == 0 {
+        0
+    } else {
+        (value.trailing_zeros() as usize).min(EXPONENT_RANGE - 1)
+    }
+}
+
+// 棋盘的对称变换，前 4 种适用于任意矩形，后 4 种（含转置）只用于正方形
+fn transform(r: usize, c: usize, rows: usize, cols: usize, symmetry: usize) -> Option<(usize, usize)> {
+    let (r, c) = if symmetry >= 4 { (c, r) } else { (r, c) };
+    if r >= rows || c >= cols {
+        return None;
+    }
+    Some(match symmetry % 4 {
+        0 => (r, c),
+        1 => (r, cols - 1 - c),
+        2 => (rows - 1 - r, c),
+        _ => (rows - 1 - r, cols - 1 - c),
+    })
+}
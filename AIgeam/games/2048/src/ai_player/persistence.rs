@@ -0,0 +1,214 @@
+use super::expectimax::Expectimax;
+use super::{AILevel, AIPlayer, BoardState, Direction, NTupleNetwork, Strategy, MAX_SEARCH_DEPTH};
+
+// 存档格式：4 字节魔数 + 2 字节版本号（小端），后面是对应版本的正文
+// 早期 save_network 直接输出 n-tuple 查找表、没有文件头，读取时按第 0 版迁移
+// 第 2 版在学习率之后增加了随机走子概率，并去掉了从未写入内容的经验表；
+// 第 1 版读取时随机走子概率按 0 处理，经验表读出后丢弃
+const MAGIC: [u8; 4] = *b"AIXP";
+const FORMAT_VERSION: u16 = 2;
+
+impl AIPlayer {
+    // 把难度、学习率、随机走子概率、策略参数和学习到的网络写成紧凑的二进制，
+    // 浏览器端可以直接把这段字节存进 IndexedDB
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+
+        bytes.push(level_code(self.difficulty));
+        bytes.extend_from_slice(&self.learning_rate.to_le_bytes());
+        bytes.extend_from_slice(&self.noise.to_le_bytes());
+        write_strategy(&mut bytes, &self.current_strategy);
+        bytes
+    }
+
+    pub fn load_state(bytes: &[u8]) -> Result<Self, String> {
+        if !bytes.starts_with(&MAGIC) {
+            return migrate_network_only(bytes);
+        }
+
+        let mut reader = ByteReader::new(&bytes[MAGIC.len()..]);
+        match u16::from_le_bytes(reader.take_array()?) {
+            version @ 1..=FORMAT_VERSION => read_body(&mut reader, version),
+            version if version > FORMAT_VERSION => {
+                Err(format!("AI state version {} is newer than supported version {}", version, FORMAT_VERSION))
+            }
+            version => Err(format!("Unknown AI state version {}", version)),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_to_file(&self, path: impl AsRef<std::path::Path>) -> Result<(), String> {
+        std::fs::write(path, self.save_state()).map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_from_file(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        Self::load_state(&bytes)
+    }
+}
+
+// 第 0 版：只有 n-tuple 网络，迁移成使用该网络的自适应 AI
+fn migrate_network_only(bytes: &[u8]) -> Result<AIPlayer, String> {
+    let network = NTupleNetwork::from_bytes(bytes)
+        .map_err(|e| format!("Unrecognized AI state: {}", e))?;
+    let mut player = AIPlayer::new(AILevel::Adaptive);
+    player.current_strategy = Strategy::AdaptiveLearning { network, last_afterstate: None };
+    Ok(player)
+}
+
+fn read_body(reader: &mut ByteReader, version: u16) -> Result<AIPlayer, String> {
+    let level = level_from_code(reader.take(1)?[0])?;
+    let mut player = AIPlayer::new(level);
+    player.learning_rate = f32::from_le_bytes(reader.take_array()?);
+    if version >= 2 {
+        player.noise = f32::from_le_bytes(reader.take_array()?);
+    }
+    player.current_strategy = read_strategy(reader)?;
+    if version == 1 {
+        skip_experience(reader)?;
+    }
+
+    if !reader.is_empty() {
+        return Err("Trailing bytes after AI state".to_string());
+    }
+    Ok(player)
+}
+
+// 第 1 版的经验表：条数，每条为棋盘和若干（方向, 估值），只校验格式不保留内容
+fn skip_experience(reader: &mut ByteReader) -> Result<(), String> {
+    let count = u32::from_le_bytes(reader.take_array()?);
+    for _ in 0..count {
+        read_board(reader)?;
+        let len = reader.take(1)?[0];
+        for _ in 0..len {
+            direction_from_code(reader.take(1)?[0])?;
+            reader.take(4)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_strategy(bytes: &mut Vec<u8>, strategy: &Strategy) {
+    match strategy {
+        Strategy::CornerMax => bytes.push(0),
+        Strategy::Greedy => bytes.push(1),
+        Strategy::AdaptiveLearning { network, .. } => {
+            bytes.push(2);
+            let table = network.to_bytes();
+            bytes.extend_from_slice(&(table.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&table);
+        }
+        Strategy::Expectimax(search) => {
+            bytes.push(3);
+            bytes.extend_from_slice(&search.depth().to_le_bytes());
+            bytes.extend_from_slice(&search.probability_cutoff().to_le_bytes());
+        }
+        Strategy::MonteCarlo { rollouts, depth } => {
+            bytes.push(4);
+            bytes.extend_from_slice(&rollouts.to_le_bytes());
+            bytes.extend_from_slice(&depth.to_le_bytes());
+        }
+    }
+}
+
+fn read_strategy(reader: &mut ByteReader) -> Result<Strategy, String> {
+    Ok(match reader.take(1)?[0] {
+        0 => Strategy::CornerMax,
+        1 => Strategy::Greedy,
+        2 => {
+            let len = u32::from_le_bytes(reader.take_array()?) as usize;
+            Strategy::AdaptiveLearning {
+                network: NTupleNetwork::from_bytes(reader.take(len)?)?,
+                last_afterstate: None,
+            }
+        }
+        3 => {
+            let depth = u32::from_le_bytes(reader.take_array()?);
+            if depth > MAX_SEARCH_DEPTH {
+                return Err(format!("Search depth {} exceeds the maximum of {}", depth, MAX_SEARCH_DEPTH));
+            }
+            let cutoff = f32::from_le_bytes(reader.take_array()?);
+            Strategy::Expectimax(Expectimax::new(depth, cutoff))
+        }
+        4 => Strategy::MonteCarlo {
+            rollouts: u32::from_le_bytes(reader.take_array()?),
+            depth: u32::from_le_bytes(reader.take_array()?),
+        },
+        tag => return Err(format!("Unknown strategy tag {}", tag)),
+    })
+}
+
+// 棋盘按行列数 + 分数 + 每格指数（0 为空格）存储
+fn read_board(reader: &mut ByteReader) -> Result<BoardState, String> {
+    let [rows, cols] = reader.take_array()?;
+    let score = u32::from_le_bytes(reader.take_array()?);
+    let cells = reader.take(rows as usize * cols as usize)?;
+    if cells.iter().any(|&exponent| exponent > 31) {
+        return Err("Invalid tile exponent in AI state".to_string());
+    }
+    let grid = cells.chunks(cols.max(1) as usize)
+        .map(|row| row.iter().map(|&exponent| if exponent == 0 { 0 } else { 1 << exponent }).collect())
+        .collect();
+    Ok(BoardState { grid, score })
+}
+
+fn level_code(level: AILevel) -> u8 {
+    match level {
+        AILevel::Easy => 0,
+        AILevel::Medium => 1,
+        AILevel::Hard => 2,
+        AILevel::Adaptive => 3,
+    }
+}
+
+fn level_from_code(code: u8) -> Result<AILevel, String> {
+    match code {
+        0 => Ok(AILevel::Easy),
+        1 => Ok(AILevel::Medium),
+        2 => Ok(AILevel::Hard),
+        3 => Ok(AILevel::Adaptive),
+        _ => Err(format!("Unknown AI level {}", code)),
+    }
+}
+
+fn direction_from_code(code: u8) -> Result<Direction, String> {
+    Direction::from_code(code).ok_or_else(|| format!("Unknown direction {}", code))
+}
+
+pub(super) struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub(super) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    pub(super) fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    pub(super) fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = match self.pos.checked_add(n) {
+            Some(end) if end <= self.bytes.len() => end,
+            _ => return Err("Unexpected end of AI data".to_string()),
+        };
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    pub(super) fn take_array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+}
@@ -0,0 +1,204 @@
+// 无界面的 AI 自我对弈评测工具
+//
+// 用法：selfplay [--games N] [--seed S] [--strategies easy,medium,hard,adaptive,montecarlo]
+//               [--format json|csv] [--threads N] [--rows R] [--cols C] [--win-tile T]
+//               [--train N] [--rollouts N] [--rollout-depth N]
+use game_2048::ai_player::{AILevel, AIPlayer, HeadlessGame};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+const USAGE: &str = "usage: selfplay [--games N] [--seed S] [--strategies easy,medium,hard,adaptive,montecarlo]
+                [--format json|csv] [--threads N] [--rows R] [--cols C] [--win-tile T]
+                [--train N] [--rollouts N] [--rollout-depth N]";
+
+// 棋盘尺寸范围，与 wasm-2048 的 GameConfig 一致
+const MIN_SIZE: usize = 3;
+const MAX_SIZE: usize = 8;
+
+struct Options {
+    games: u32,
+    seed: u64,
+    strategies: Vec<String>,
+    format: String,
+    threads: usize,
+    rows: usize,
+    cols: usize,
+    win_tile: u32,
+    train: u32,
+    rollouts: u32,
+    rollout_depth: u32,
+}
+
+#[derive(Serialize)]
+struct StrategyReport {
+    strategy: String,
+    games: u32,
+    win_rate: f64,
+    average_score: f64,
+    median_score: u32,
+    max_tiles: BTreeMap<u32, u32>,
+    illegal_moves: u32,
+    moves_per_second: f64,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        games: 100,
+        seed: 2048,
+        strategies: vec!["easy", "medium", "hard", "adaptive", "montecarlo"]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        format: "json".to_string(),
+        threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        rows: 4,
+        cols: 4,
+        win_tile: 2048,
+        train: 0,
+        rollouts: 100,
+        rollout_depth: 20,
+    };
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    for pair in args.chunks(2) {
+        let value = pair.get(1).ok_or_else(|| format!("Missing value for {}", pair[0]))?;
+        let number = || value.parse::<u64>().map_err(|_| format!("Invalid number for {}: {}", pair[0], value));
+        match pair[0].as_str() {
+            "--games" => options.games = number()? as u32,
+            "--seed" => options.seed = number()?,
+            "--strategies" => options.strategies = value.split(',').map(String::from).collect(),
+            "--format" => options.format = value.clone(),
+            "--threads" => options.threads = (number()? as usize).max(1),
+            "--rows" => options.rows = number()? as usize,
+            "--cols" => options.cols = number()? as usize,
+            "--win-tile" => options.win_tile = number()? as u32,
+            "--train" => options.train = number()? as u32,
+            "--rollouts" => options.rollouts = number()? as u32,
+            "--rollout-depth" => options.rollout_depth = number()? as u32,
+            other => return Err(format!("Unknown option {}", other)),
+        }
+    }
+
+    if options.format != "json" && options.format != "csv" {
+        return Err(format!("Unknown format {}", options.format));
+    }
+    if !(MIN_SIZE..=MAX_SIZE).contains(&options.rows) || !(MIN_SIZE..=MAX_SIZE).contains(&options.cols) {
+        return Err(format!(
+            "Board must be between {}x{} and {}x{}, got {}x{}",
+            MIN_SIZE, MIN_SIZE, MAX_SIZE, MAX_SIZE, options.rows, options.cols
+        ));
+    }
+    Ok(options)
+}
+
+fn create_player(name: &str, options: &Options) -> Result<AIPlayer, String> {
+    let mut player = match name {
+        "easy" => AIPlayer::new(AILevel::Easy),
+        "medium" => AIPlayer::new(AILevel::Medium),
+        "hard" => AIPlayer::new(AILevel::Hard),
+        "adaptive" => AIPlayer::new(AILevel::Adaptive),
+        "montecarlo" => AIPlayer::with_rollout_budget(options.rollouts, options.rollout_depth),
+        other => return Err(format!("Unknown strategy {}", other)),
+    };
+    player.seed_rng(options.seed);
+    if options.train > 0 {
+        player.train(options.train, options.rows, options.cols);
+    }
+    Ok(player)
+}
+
+// 把 N 局按种子分给各个线程，每局都从同一个玩家副本开始，结果与线程数无关
+fn run_strategy(name: &str, options: &Options) -> Result<StrategyReport, String> {
+    let player = create_player(name, options)?;
+    let seeds: Vec<u64> = (0..options.games as u64).map(|i| options.seed.wrapping_add(i)).collect();
+    let chunk_size = seeds.len().div_ceil(options.threads).max(1);
+
+    let started = Instant::now();
+    let mut results: Vec<HeadlessGame> = std::thread::scope(|scope| {
+        let handles: Vec<_> = seeds.chunks(chunk_size)
+            .map(|chunk| {
+                let player = &player;
+                scope.spawn(move || {
+                    chunk.iter()
+                        .map(|&seed| {
+                            let mut player = player.clone();
+                            player.seed_rng(seed);
+                            player.play_headless(seed, options.rows, options.cols, options.win_tile)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles.into_iter()
+            .flat_map(|handle| handle.join().expect("self-play thread panicked"))
+            .collect()
+    });
+    let elapsed = started.elapsed().as_secs_f64();
+
+    results.sort_by_key(|game| game.score);
+    let games = results.len() as u32;
+    let total_moves: u64 = results.iter().map(|game| game.moves as u64).sum();
+    let mut max_tiles = BTreeMap::new();
+    for game in &results {
+        *max_tiles.entry(game.max_tile).or_insert(0) += 1;
+    }
+
+    Ok(StrategyReport {
+        strategy: name.to_string(),
+        games,
+        win_rate: results.iter().filter(|game| game.won).count() as f64 / games.max(1) as f64,
+        average_score: results.iter().map(|game| game.score as f64).sum::<f64>() / games.max(1) as f64,
+        median_score: results.get(results.len() / 2).map_or(0, |game| game.score),
+        max_tiles,
+        illegal_moves: results.iter().map(|game| game.illegal_moves).sum(),
+        moves_per_second: if elapsed > 0.0 { total_moves as f64 / elapsed } else { 0.0 },
+    })
+}
+
+fn print_csv(reports: &[StrategyReport]) {
+    println!("strategy,games,win_rate,average_score,median_score,illegal_moves,moves_per_second,max_tiles");
+    for report in reports {
+        let tiles: Vec<String> = report.max_tiles.iter()
+            .map(|(tile, count)| format!("{}:{}", tile, count))
+            .collect();
+        println!(
+            "{},{},{:.4},{:.1},{},{},{:.1},{}",
+            report.strategy,
+            report.games,
+            report.win_rate,
+            report.average_score,
+            report.median_score,
+            report.illegal_moves,
+            report.moves_per_second,
+            tiles.join(" ")
+        );
+    }
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut reports = Vec::new();
+    for name in &options.strategies {
+        match run_strategy(name, &options) {
+            Ok(report) => reports.push(report),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    if options.format == "csv" {
+        print_csv(&reports);
+    } else {
+        println!("{}", serde_json::to_string_pretty(&reports).unwrap());
+    }
+}
@@ -0,0 +1,49 @@
+// 对战难度校准的测试：强度要能作用到每个难度的 AI 上，校准状态要能随存档保存
+use game_2048::ai_player::{AILevel, AIPlayer, PlayerGame, SkillCalibrator};
+
+fn game(score: u32) -> PlayerGame {
+    PlayerGame { score, max_tile: 256, error_rate: 0.1, move_speed: 2.0 }
+}
+
+#[test]
+fn weakest_handicap_adds_noise_without_search() {
+    let mut weakest = SkillCalibrator::new(0.5);
+    for _ in 0..5 {
+        weakest.record_game(game(100), 100_000);
+    }
+    assert_eq!(weakest.strength(), 0.0);
+    let handicap = weakest.handicap();
+    assert_eq!(handicap.depth, 1);
+    assert!(handicap.noise_without_search() > handicap.noise);
+
+    // 自适应 AI 没有搜索深度，削弱只能体现在存档里的随机走子概率上
+    let mut adaptive = AIPlayer::new(AILevel::Adaptive);
+    let before = adaptive.save_state();
+    weakest.apply(&mut adaptive);
+    assert_ne!(adaptive.save_state(), before);
+}
+
+#[test]
+fn full_strength_has_no_handicap() {
+    let mut calibrator = SkillCalibrator::new(0.5);
+    for _ in 0..5 {
+        calibrator.record_game(game(100_000), 100);
+    }
+    assert_eq!(calibrator.strength(), 1.0);
+    assert_eq!(calibrator.handicap().noise_without_search(), 0.0);
+}
+
+#[test]
+fn calibrator_state_round_trips() {
+    let mut calibrator = SkillCalibrator::new(0.5);
+    for score in [1200, 3400, 800] {
+        calibrator.record_game(game(score), 5000);
+    }
+    let bytes = calibrator.to_bytes();
+    let loaded = SkillCalibrator::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded.strength(), calibrator.strength());
+    assert_eq!(loaded.to_bytes(), bytes);
+
+    assert!(SkillCalibrator::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(SkillCalibrator::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+}
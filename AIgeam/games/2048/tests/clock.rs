@@ -0,0 +1,95 @@
+// 限时选步的测试：注入每读一次就前进固定步长的时钟，按读取次数检查时间预算何时用完
+use std::cell::Cell;
+use std::time::Duration;
+
+use game_2048::ai_player::{AILevel, AIPlayer, BoardState, Clock, Direction, Handicap};
+
+struct SteppedClock {
+    now: Cell<Duration>,
+    step: Duration,
+    reads: Cell<u32>,
+}
+
+impl SteppedClock {
+    fn new(step_ms: u64) -> Self {
+        Self { now: Cell::new(Duration::ZERO), step: Duration::from_millis(step_ms), reads: Cell::new(0) }
+    }
+}
+
+impl Clock for SteppedClock {
+    fn now(&self) -> Duration {
+        let now = self.now.get();
+        self.now.set(now + self.step);
+        self.reads.set(self.reads.get() + 1);
+        now
+    }
+}
+
+fn board() -> BoardState {
+    BoardState {
+        grid: vec![vec![2, 4, 8, 16], vec![0, 2, 4, 8], vec![0, 0, 2, 4], vec![0, 0, 0, 2]],
+        score: 0,
+    }
+}
+
+fn is_legal(state: &BoardState, direction: Direction) -> bool {
+    let analysis = AIPlayer::new(AILevel::Medium).analyze(state.grid.clone(), state.score);
+    analysis.directions.iter().any(|d| d.direction == direction && d.legal)
+}
+
+#[test]
+fn expired_budget_still_returns_the_depth_one_move() {
+    let mut ai = AIPlayer::new(AILevel::Hard);
+    let clock = SteppedClock::new(1);
+    let direction = ai.get_next_move_with_clock(&board(), Duration::ZERO, &clock);
+    assert!(is_legal(&board(), direction));
+    // 读一次算出截止时间，第一层搜索完成后再读一次就发现已经超时
+    assert_eq!(clock.reads.get(), 2);
+}
+
+#[test]
+fn expectimax_stops_on_the_first_read_past_the_deadline() {
+    for budget_ms in [5, 20, 50] {
+        let mut ai = AIPlayer::new(AILevel::Hard);
+        let clock = SteppedClock::new(1);
+        let direction = ai.get_next_move_with_clock(&board(), Duration::from_millis(budget_ms), &clock);
+        assert!(is_legal(&board(), direction));
+        // 第 1 次读取得到 0ms，第 budget_ms + 1 次读取到达截止时间，之后不再读取
+        assert_eq!(clock.reads.get() as u64, budget_ms + 1);
+    }
+}
+
+#[test]
+fn expectimax_stops_at_its_own_depth() {
+    // 时间充足时搜完困难 AI 的 3 层就停下，结果与不限时相同
+    let mut ai = AIPlayer::new(AILevel::Hard);
+    let clock = SteppedClock::new(1);
+    let direction = ai.get_next_move_with_clock(&board(), Duration::from_secs(10), &clock);
+    assert_eq!(direction, AIPlayer::new(AILevel::Hard).get_next_move(&board()));
+    assert!(clock.reads.get() < 10_000);
+}
+
+#[test]
+fn handicapped_search_does_not_deepen_past_depth_one() {
+    let mut ai = AIPlayer::new(AILevel::Hard);
+    ai.set_handicap(Handicap { depth: 1, noise: 0.0 });
+    let clock = SteppedClock::new(1);
+    let direction = ai.get_next_move_with_clock(&board(), Duration::from_secs(10), &clock);
+    // 读一次算出截止时间，第一层搜索后再读一次，没有更深的一层
+    assert_eq!(clock.reads.get(), 2);
+
+    let mut untimed = AIPlayer::new(AILevel::Hard);
+    untimed.set_handicap(Handicap { depth: 1, noise: 0.0 });
+    assert_eq!(direction, untimed.get_next_move(&board()));
+}
+
+#[test]
+fn monte_carlo_runs_one_round_per_clock_step_until_the_deadline() {
+    let mut ai = AIPlayer::with_rollout_budget(100, 10);
+    ai.seed_rng(3);
+    let clock = SteppedClock::new(10);
+    let direction = ai.get_next_move_with_clock(&board(), Duration::from_millis(100), &clock);
+    assert!(is_legal(&board(), direction));
+    // 每轮模拟后检查一次时间：10 轮后到达 100ms
+    assert_eq!(clock.reads.get(), 11);
+}
@@ -0,0 +1,27 @@
+// 期望最大搜索的测试：评估函数全为负分时，搜索仍要避开必死的走法
+use game_2048::ai_player::{AILevel, AIPlayer, BoardEvaluator, BoardState, Direction};
+
+struct AlwaysNegative;
+
+impl BoardEvaluator for AlwaysNegative {
+    fn evaluate(&self, _board: &BoardState) -> f32 {
+        -1.0
+    }
+}
+
+#[test]
+fn dead_end_scores_below_negative_positions() {
+    // 向左后只剩 (3,3) 一个空格，四周是 8 和 16，无论生成 2 还是 4 都无路可走；
+    // 其他方向都留有活路
+    let state = BoardState {
+        grid: vec![
+            vec![2, 4, 2, 4],
+            vec![4, 2, 4, 2],
+            vec![2, 4, 2, 16],
+            vec![4, 2, 0, 8],
+        ],
+        score: 0,
+    };
+    let mut player = AIPlayer::new(AILevel::Hard).with_evaluator(AlwaysNegative);
+    assert_ne!(player.get_next_move(&state), Direction::Left);
+}
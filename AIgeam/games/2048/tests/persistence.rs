@@ -0,0 +1,65 @@
+// AI 存档读取的测试：存档来自浏览器存储或文件，内容损坏时必须返回错误而不是中止进程
+use game_2048::ai_player::{AILevel, AIPlayer, NTupleNetwork};
+
+#[test]
+fn saved_state_round_trips() {
+    for level in [AILevel::Easy, AILevel::Medium, AILevel::Hard, AILevel::Adaptive] {
+        let player = AIPlayer::new(level);
+        let bytes = player.save_state();
+        assert_eq!(AIPlayer::load_state(&bytes).unwrap().save_state(), bytes);
+    }
+}
+
+#[test]
+fn huge_tuple_count_is_rejected_without_allocating() {
+    let bytes = u32::MAX.to_le_bytes();
+    assert!(NTupleNetwork::from_bytes(&bytes).is_err());
+    // 没有魔数的数据按第 0 版（只有网络）读取
+    assert!(AIPlayer::load_state(&bytes).is_err());
+}
+
+// 第 1 版存档：简单难度、学习率 0.1，后面接经验表
+fn version_one(experience: &[u8]) -> Vec<u8> {
+    let mut bytes = b"AIXP".to_vec();
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(&0.1f32.to_le_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(experience);
+    bytes
+}
+
+#[test]
+fn huge_experience_count_is_rejected_without_allocating() {
+    assert!(AIPlayer::load_state(&version_one(&u32::MAX.to_le_bytes())).is_err());
+}
+
+#[test]
+fn version_one_experience_is_dropped() {
+    // 一条经验：2x2 棋盘、分数 4、一格为 4，向左估值 1.5
+    let mut experience = 1u32.to_le_bytes().to_vec();
+    experience.extend_from_slice(&[2, 2]);
+    experience.extend_from_slice(&4u32.to_le_bytes());
+    experience.extend_from_slice(&[2, 0, 0, 0]);
+    experience.extend_from_slice(&[1, 3]);
+    experience.extend_from_slice(&1.5f32.to_le_bytes());
+
+    let player = AIPlayer::load_state(&version_one(&experience)).unwrap();
+    assert_eq!(player.save_state(), AIPlayer::new(AILevel::Easy).save_state());
+
+    // 经验表的方向不合法时仍然报错
+    let last = experience.len() - 5;
+    experience[last] = 9;
+    assert!(AIPlayer::load_state(&version_one(&experience)).is_err());
+}
+
+#[test]
+fn search_depth_beyond_the_limit_is_rejected() {
+    let mut bytes = AIPlayer::new(AILevel::Hard).save_state();
+    // 期望最大搜索的存档以 4 字节深度和 4 字节概率阈值结尾
+    let depth = bytes.len() - 8;
+    bytes[depth..depth + 4].copy_from_slice(&9u32.to_le_bytes());
+    assert!(AIPlayer::load_state(&bytes).is_err());
+    bytes[depth..depth + 4].copy_from_slice(&8u32.to_le_bytes());
+    assert!(AIPlayer::load_state(&bytes).is_ok());
+}
@@ -0,0 +1,268 @@
+use game_2048::ai_player::{AILevel, AIPlayer, Direction, MoveAnalysis};
+use wasm_2048::{Game2048, GameStatus, Replay, ReplayEvent};
+
+pub const LEVELS: [AILevel; 4] = [AILevel::Easy, AILevel::Medium, AILevel::Hard, AILevel::Adaptive];
+
+pub fn level_name(level: AILevel) -> &'static str {
+    match level {
+        AILevel::Easy => "easy",
+        AILevel::Medium => "medium",
+        AILevel::Hard => "hard",
+        AILevel::Adaptive => "adaptive",
+    }
+}
+
+pub fn parse_level(name: &str) -> Result<AILevel, String> {
+    LEVELS.into_iter()
+        .find(|&level| level_name(level) == name)
+        .ok_or_else(|| format!("Unknown AI level {}", name))
+}
+
+// 回放模式：position 为已经应用的操作数，当前局面由录像的前 position 个操作重放得到
+pub struct ReplayCursor {
+    pub replay: Replay,
+    pub position: usize,
+}
+
+impl ReplayCursor {
+    pub fn last_event(&self) -> Option<&ReplayEvent> {
+        self.position.checked_sub(1).map(|index| &self.replay.events[index])
+    }
+}
+
+pub struct App {
+    pub game: Game2048,
+    pub level: AILevel,
+    pub autopilot: bool,
+    pub replay: Option<ReplayCursor>,
+    pub message: String,
+    ai: AIPlayer,
+    // 当前局面的各方向估值，局面或 AI 难度变化后重新计算
+    analysis: Option<MoveAnalysis>,
+    save_path: String,
+}
+
+impl App {
+    pub fn new(seed: u64, level: AILevel, save_path: String) -> Self {
+        Self {
+            game: Game2048::with_seed(seed),
+            level,
+            autopilot: false,
+            replay: None,
+            message: format!("New game, seed {}", seed),
+            ai: create_ai(level, seed),
+            analysis: None,
+            save_path,
+        }
+    }
+
+    pub fn from_replay(replay: Replay, level: AILevel, save_path: String) -> Result<Self, String> {
+        // 先完整重放一遍，确认录像本身有效
+        Game2048::replay(&replay)?;
+        let mut app = Self::new(replay.seed, level, save_path);
+        app.message = format!("Loaded replay with {} events", replay.events.len());
+        app.replay = Some(ReplayCursor { replay, position: 0 });
+        app.seek(0)?;
+        Ok(app)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.game.seed()
+    }
+
+    pub fn analysis(&mut self) -> &MoveAnalysis {
+        let grid = self.game.board().to_grid();
+        let score = self.game.score();
+        self.analysis.get_or_insert_with(|| self.ai.analyze(grid, score))
+    }
+
+    pub fn play(&mut self, direction: Direction) {
+        if self.replay.is_some() {
+            return;
+        }
+        let result = self.game.play(direction.code());
+        if result.moved {
+            self.analysis = None;
+        }
+        if result.status == GameStatus::Lost {
+            self.autopilot = false;
+            self.message = format!("Game over, score {}", self.game.score());
+        }
+    }
+
+    // 自动驾驶走一步：直接采用面板上已经算好的推荐方向，不再单独搜索一遍；
+    // 与手动输入走同一条路径，录像里不区分
+    pub fn autopilot_step(&mut self) {
+        match self.analysis().recommended {
+            Some(direction) => self.play(direction),
+            None => self.autopilot = false,
+        }
+    }
+
+    pub fn toggle_autopilot(&mut self) {
+        if self.replay.is_some() {
+            self.message = "Take over the replay before starting the autopilot".to_string();
+            return;
+        }
+        self.autopilot = !self.autopilot && self.game.status() != GameStatus::Lost;
+        self.message = format!("Autopilot {}", if self.autopilot { "on" } else { "off" });
+    }
+
+    pub fn cycle_level(&mut self) {
+        let index = LEVELS.iter().position(|&level| level == self.level).unwrap_or(0);
+        self.level = LEVELS[(index + 1) % LEVELS.len()];
+        self.ai = create_ai(self.level, self.seed());
+        self.analysis = None;
+        self.message = format!("AI level {}", level_name(self.level));
+    }
+
+    pub fn undo(&mut self) {
+        if self.replay.is_none() && self.game.undo() {
+            self.analysis = None;
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if self.replay.is_none() && self.game.redo() {
+            self.analysis = None;
+        }
+    }
+
+    pub fn restart(&mut self) {
+        let seed = rand::random();
+        *self = Self::new(seed, self.level, std::mem::take(&mut self.save_path));
+    }
+
+    pub fn save_replay(&mut self) {
+        let result = serde_json::to_string_pretty(self.game.replay_log())
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(&self.save_path, json).map_err(|e| e.to_string()));
+        self.message = match result {
+            Ok(()) => format!("Replay saved to {}", self.save_path),
+            Err(error) => format!("Failed to save replay: {}", error),
+        };
+    }
+
+    // 在录像中前后移动 delta 个操作，超出范围时停在开头或结尾
+    pub fn step(&mut self, delta: isize) {
+        if let Some(cursor) = &self.replay {
+            let target = cursor.position.saturating_add_signed(delta).min(cursor.replay.events.len());
+            if let Err(error) = self.seek(target) {
+                self.message = error;
+            }
+        }
+    }
+
+    // 从回放的当前位置接着手动游戏，之后的操作会覆盖录像的剩余部分
+    pub fn take_over(&mut self) {
+        if let Some(cursor) = self.replay.take() {
+            self.message = format!("Playing from event {}", cursor.position);
+        }
+    }
+
+    fn seek(&mut self, position: usize) -> Result<(), String> {
+        let cursor = self.replay.as_mut().ok_or("Not in replay mode")?;
+        let prefix = Replay {
+            events: cursor.replay.events[..position].to_vec(),
+            ..cursor.replay.clone()
+        };
+        self.game = Game2048::replay(&prefix)?;
+        cursor.position = position;
+        self.analysis = None;
+        Ok(())
+    }
+}
+
+fn create_ai(level: AILevel, seed: u64) -> AIPlayer {
+    let mut ai = AIPlayer::new(level);
+    ai.seed_rng(seed);
+    ai
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 用简单难度的 AI 自动走若干步，得到一份真实的录像
+    fn recorded_replay(moves: usize) -> Replay {
+        let mut app = App::new(7, AILevel::Easy, String::new());
+        for _ in 0..moves {
+            app.autopilot_step();
+        }
+        app.undo();
+        app.game.replay_log().clone()
+    }
+
+    fn board_after(replay: &Replay, events: usize) -> Vec<Vec<u32>> {
+        let prefix = Replay { events: replay.events[..events].to_vec(), ..replay.clone() };
+        Game2048::replay(&prefix).unwrap().board().to_grid()
+    }
+
+    #[test]
+    fn replay_starts_at_the_opening_position() {
+        let replay = recorded_replay(10);
+        let app = App::from_replay(replay.clone(), AILevel::Easy, String::new()).unwrap();
+        let cursor = app.replay.as_ref().unwrap();
+        assert_eq!(cursor.position, 0);
+        assert!(cursor.last_event().is_none());
+        assert_eq!(app.game.board().to_grid(), board_after(&replay, 0));
+    }
+
+    #[test]
+    fn stepping_stops_at_both_ends_of_the_replay() {
+        let replay = recorded_replay(10);
+        let events = replay.events.len();
+        let mut app = App::from_replay(replay.clone(), AILevel::Easy, String::new()).unwrap();
+
+        app.step(-1);
+        assert_eq!(app.replay.as_ref().unwrap().position, 0);
+
+        app.step(isize::MAX);
+        assert_eq!(app.replay.as_ref().unwrap().position, events);
+        assert_eq!(app.replay.as_ref().unwrap().last_event(), Some(&ReplayEvent::Undo));
+        assert_eq!(app.game.board().to_grid(), board_after(&replay, events));
+
+        app.step(1);
+        assert_eq!(app.replay.as_ref().unwrap().position, events);
+
+        app.step(-1);
+        assert_eq!(app.replay.as_ref().unwrap().position, events - 1);
+        assert_eq!(app.game.board().to_grid(), board_after(&replay, events - 1));
+
+        app.step(isize::MIN);
+        assert_eq!(app.replay.as_ref().unwrap().position, 0);
+        assert_eq!(app.game.board().to_grid(), board_after(&replay, 0));
+    }
+
+    #[test]
+    fn replay_ignores_moves_until_taken_over() {
+        let replay = recorded_replay(10);
+        let mut app = App::from_replay(replay, AILevel::Easy, String::new()).unwrap();
+        app.step(3);
+        let board = app.game.board().to_grid();
+
+        app.toggle_autopilot();
+        assert!(!app.autopilot);
+        for direction in Direction::ALL {
+            app.play(direction);
+        }
+        assert_eq!(app.game.board().to_grid(), board);
+
+        app.take_over();
+        assert!(app.replay.is_none());
+        let before = app.game.replay_log().events.len();
+        app.autopilot_step();
+        assert_eq!(app.game.replay_log().events.len(), before + 1);
+    }
+
+    #[test]
+    fn autopilot_plays_the_recommended_move() {
+        let mut app = App::new(11, AILevel::Medium, String::new());
+        for _ in 0..5 {
+            let recommended = app.analysis().recommended.unwrap();
+            app.autopilot_step();
+            let last = app.game.replay_log().events.last().cloned();
+            assert!(matches!(last, Some(ReplayEvent::Move { direction, spawned: Some(_) }) if direction == recommended.code()));
+        }
+    }
+}
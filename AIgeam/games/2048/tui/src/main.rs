@@ -0,0 +1,164 @@
+// 终端版 2048，用于在不启动网页的情况下调试引擎和 AI 策略
+//
+// 用法：game2048-tui [--seed S] [--level easy|medium|hard|adaptive] [--replay FILE]
+//                    [--save FILE] [--delay MS]
+//
+// 方向键移动，a 开关 AI 自动驾驶，l 切换 AI 难度，右侧实时显示 AI 对每个方向的估值；
+// --replay 载入保存的录像（JSON），用左右方向键逐步查看
+mod app;
+mod view;
+
+use std::io;
+use std::time::Duration;
+
+use crossterm::cursor::{Hide, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use game_2048::ai_player::{AILevel, Direction};
+use wasm_2048::Replay;
+
+use app::App;
+
+struct Options {
+    seed: u64,
+    level: AILevel,
+    replay: Option<String>,
+    save: String,
+    delay_ms: u64,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        seed: rand::random(),
+        level: AILevel::Hard,
+        replay: None,
+        save: "2048-replay.json".to_string(),
+        delay_ms: 150,
+    };
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    for pair in args.chunks(2) {
+        let value = pair.get(1).ok_or_else(|| format!("Missing value for {}", pair[0]))?;
+        let number = || value.parse::<u64>().map_err(|_| format!("Invalid number for {}: {}", pair[0], value));
+        match pair[0].as_str() {
+            "--seed" => options.seed = number()?,
+            "--level" => options.level = app::parse_level(value)?,
+            "--replay" => options.replay = Some(value.clone()),
+            "--save" => options.save = value.clone(),
+            "--delay" => options.delay_ms = number()?,
+            other => return Err(format!("Unknown option {}", other)),
+        }
+    }
+    Ok(options)
+}
+
+fn create_app(options: &Options) -> Result<App, String> {
+    match &options.replay {
+        Some(path) => {
+            let json = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let replay: Replay = serde_json::from_str(&json).map_err(|e| format!("Invalid replay {}: {}", path, e))?;
+            App::from_replay(replay, options.level, options.save.clone())
+        }
+        None => Ok(App::new(options.seed, options.level, options.save.clone())),
+    }
+}
+
+// 进入原始模式和备用屏幕，离开作用域（包括 panic）时恢复终端
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn arrow_direction(code: KeyCode) -> Option<Direction> {
+    match code {
+        KeyCode::Up => Some(Direction::Up),
+        KeyCode::Right => Some(Direction::Right),
+        KeyCode::Down => Some(Direction::Down),
+        KeyCode::Left => Some(Direction::Left),
+        _ => None,
+    }
+}
+
+// 返回 false 表示退出
+fn handle_key(app: &mut App, key: KeyEvent) -> bool {
+    let quit = key.code == KeyCode::Char('q')
+        || key.code == KeyCode::Esc
+        || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL));
+    if quit {
+        return false;
+    }
+
+    if app.replay.is_some() {
+        match key.code {
+            KeyCode::Right => app.step(1),
+            KeyCode::Left => app.step(-1),
+            KeyCode::PageDown => app.step(10),
+            KeyCode::PageUp => app.step(-10),
+            KeyCode::Home => app.step(isize::MIN),
+            KeyCode::End => app.step(isize::MAX),
+            KeyCode::Enter => app.take_over(),
+            KeyCode::Char('l') => app.cycle_level(),
+            _ => {}
+        }
+        return true;
+    }
+
+    if let Some(direction) = arrow_direction(key.code) {
+        app.play(direction);
+        return true;
+    }
+    match key.code {
+        KeyCode::Char('a') => app.toggle_autopilot(),
+        KeyCode::Char('l') => app.cycle_level(),
+        KeyCode::Char('z') => app.undo(),
+        KeyCode::Char('y') => app.redo(),
+        KeyCode::Char('r') => app.restart(),
+        KeyCode::Char('s') => app.save_replay(),
+        _ => {}
+    }
+    true
+}
+
+fn run(app: &mut App, delay: Duration) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    loop {
+        view::draw(&mut stdout, app)?;
+
+        // 自动驾驶时每隔 delay 走一步，期间的按键照常处理
+        let timeout = if app.autopilot { delay } else { Duration::from_secs(3600) };
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !handle_key(app, key) {
+                    return Ok(());
+                }
+            }
+        } else if app.autopilot {
+            app.autopilot_step();
+        }
+    }
+}
+
+fn main() {
+    let result = parse_options().and_then(|options| {
+        let mut app = create_app(&options)?;
+        let _guard = TerminalGuard::enter().map_err(|e| e.to_string())?;
+        run(&mut app, Duration::from_millis(options.delay_ms)).map_err(|e| e.to_string())
+    });
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
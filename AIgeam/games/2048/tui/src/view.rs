@@ -0,0 +1,128 @@
+use std::io::{self, Write};
+
+use crossterm::cursor::MoveTo;
+use crossterm::queue;
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{Clear, ClearType};
+use game_2048::ai_player::Direction;
+use wasm_2048::ReplayEvent;
+
+use crate::app::{level_name, App};
+
+const CELL_WIDTH: usize = 7;
+
+// 方块背景色（256 色），按指数取，超出的大方块沿用最后一种
+const TILE_COLORS: [u8; 12] = [236, 230, 223, 215, 209, 203, 196, 228, 227, 226, 220, 214];
+
+fn tile_color(value: u32) -> Color {
+    let exponent = if value == 0 { 0 } else { value.trailing_zeros() as usize };
+    Color::AnsiValue(TILE_COLORS[exponent.min(TILE_COLORS.len() - 1)])
+}
+
+fn arrow(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "↑",
+        Direction::Right => "→",
+        Direction::Down => "↓",
+        Direction::Left => "←",
+    }
+}
+
+fn describe_event(event: &ReplayEvent) -> String {
+    match event {
+        ReplayEvent::Move { direction, .. } => match Direction::from_code(*direction) {
+            Some(direction) => format!("move {}", arrow(direction)),
+            None => format!("move {}", direction),
+        },
+        ReplayEvent::Undo => "undo".to_string(),
+        ReplayEvent::Redo => "redo".to_string(),
+        ReplayEvent::PowerUp { power_up } => format!("power-up {:?}", power_up),
+        ReplayEvent::Blocker { tile } => format!("blocker {} at ({}, {})", tile.value, tile.row, tile.col),
+    }
+}
+
+// 左侧为棋盘，右侧为 AI 面板，底部为消息和按键说明；每次重画整个屏幕
+pub fn draw(out: &mut impl Write, app: &mut App) -> io::Result<()> {
+    queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+    queue!(
+        out,
+        SetAttribute(Attribute::Bold),
+        Print(format!("2048  score {}  {:?}", app.game.score(), app.game.status())),
+        SetAttribute(Attribute::Reset),
+        Print(format!("  seed {}", app.seed())),
+    )?;
+
+    let grid = app.game.board().to_grid();
+    for (i, row) in grid.iter().enumerate() {
+        let y = 2 + i as u16 * 2;
+        for (j, &value) in row.iter().enumerate() {
+            let label = if value == 0 { String::new() } else { value.to_string() };
+            let foreground = if value <= 4 { Color::AnsiValue(238) } else { Color::White };
+            queue!(
+                out,
+                MoveTo((j * (CELL_WIDTH + 1)) as u16, y),
+                SetBackgroundColor(tile_color(value)),
+                SetForegroundColor(foreground),
+                Print(format!("{:^width$}", label, width = CELL_WIDTH)),
+                ResetColor,
+            )?;
+        }
+    }
+
+    let panel_x = (grid.first().map_or(0, |row| row.len()) * (CELL_WIDTH + 1) + 3) as u16;
+    let autopilot = if app.autopilot { "on" } else { "off" };
+    queue!(
+        out,
+        MoveTo(panel_x, 2),
+        Print(format!("AI {}  autopilot {}", level_name(app.level), autopilot)),
+    )?;
+
+    let analysis = app.analysis().clone();
+    for (k, direction) in analysis.directions.iter().enumerate() {
+        let recommended = analysis.recommended == Some(direction.direction);
+        let value = match direction.expected_score {
+            Some(value) => format!("{:>12.1}", value),
+            None => format!("{:>12}", "illegal"),
+        };
+        queue!(out, MoveTo(panel_x, 4 + k as u16))?;
+        if recommended {
+            queue!(out, SetAttribute(Attribute::Bold), SetForegroundColor(Color::Green))?;
+        }
+        queue!(
+            out,
+            Print(format!("{} {}{}", arrow(direction.direction), value, if recommended { "  *" } else { "" })),
+            ResetColor,
+            SetAttribute(Attribute::Reset),
+        )?;
+    }
+    queue!(
+        out,
+        MoveTo(panel_x, 9),
+        Print(format!("confidence {:.2}", analysis.confidence)),
+    )?;
+
+    let bottom = 3 + grid.len() as u16 * 2;
+    if let Some(cursor) = &app.replay {
+        let last = cursor.last_event().map_or("start".to_string(), describe_event);
+        queue!(
+            out,
+            MoveTo(0, bottom),
+            Print(format!("replay {}/{}  {}", cursor.position, cursor.replay.events.len(), last)),
+        )?;
+    }
+    queue!(out, MoveTo(0, bottom + 1), Print(&app.message))?;
+
+    let help = if app.replay.is_some() {
+        "←/→ step  PgUp/PgDn ±10  Home/End  l level  enter take over  q quit"
+    } else {
+        "arrows move  a autopilot  l level  z undo  y redo  r restart  s save replay  q quit"
+    };
+    queue!(
+        out,
+        MoveTo(0, bottom + 3),
+        SetForegroundColor(Color::DarkGrey),
+        Print(help),
+        ResetColor,
+    )?;
+    out.flush()
+}
@@ -0,0 +1,128 @@
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+use game_2048::ai_player::{AILevel, AIPlayer, BoardState, PlayerGame, SkillCalibrator};
+
+use crate::bitboard;
+use crate::Game2048;
+
+// 存档格式：魔数、AI 存档的长度（小端 u32）和内容，然后是难度校准的状态
+const SAVE_MAGIC: [u8; 4] = *b"AIOP";
+// 得分对数上下 0.5 以内（约 ±40%）视为势均力敌
+const CALIBRATION_BAND: f32 = 0.5;
+
+// 浏览器端的 AI 对手，学习进度可以用 save() 导出字节存进 IndexedDB，下次用 load() 恢复
+#[wasm_bindgen]
+pub struct AIOpponent {
+    player: AIPlayer,
+    calibrator: SkillCalibrator,
+}
+
+#[wasm_bindgen]
+impl AIOpponent {
+    // level: 0 简单，1 中等，2 困难，3 自适应
+    #[wasm_bindgen(constructor)]
+    pub fn new(level: u8) -> Result<AIOpponent, JsValue> {
+        Ok(Self::from_player(AIPlayer::new(ai_level(level)?)))
+    }
+
+    // 带着玩家已有的对局记录（PlayerGame 数组）开始，第一局就按玩家水平校准
+    pub fn from_history(level: u8, games: JsValue) -> Result<AIOpponent, JsValue> {
+        let games: Vec<PlayerGame> = serde_wasm_bindgen::from_value(games)?;
+        Ok(Self::with_history(AIPlayer::new(ai_level(level)?), games))
+    }
+
+    pub fn load(bytes: &[u8]) -> Result<AIOpponent, JsValue> {
+        Self::from_bytes(bytes).map_err(|e| JsValue::from_str(&e))
+    }
+
+    pub fn save(&self) -> Vec<u8> {
+        let player = self.player.save_state();
+        let mut bytes = SAVE_MAGIC.to_vec();
+        bytes.extend_from_slice(&(player.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&player);
+        bytes.extend_from_slice(&self.calibrator.to_bytes());
+        bytes
+    }
+
+    // 当前的对战强度，0 最弱，1 为不设限
+    pub fn strength(&self) -> f32 {
+        self.calibrator.strength()
+    }
+
+    // 返回方向编号，与 Game2048::move_tiles 一致
+    pub fn next_move(&mut self, game: &Game2048) -> u8 {
+        let state = BoardState { grid: game.board().to_grid(), score: game.score() };
+        self.player.get_next_move(&state) as u8
+    }
+
+    // 限时选步，budget_ms 用完就返回目前最好的方向，避免卡住主线程
+    pub fn next_move_within(&mut self, game: &Game2048, budget_ms: f64) -> u8 {
+        let state = BoardState { grid: game.board().to_grid(), score: game.score() };
+        let budget = Duration::from_secs_f64(budget_ms.max(0.0) / 1000.0);
+        // 浏览器里没有 std::time::Instant，用 Date.now() 计时
+        #[cfg(target_arch = "wasm32")]
+        let direction = {
+            let clock = || Duration::from_secs_f64(js_sys::Date::now() / 1000.0);
+            self.player.get_next_move_with_clock(&state, budget, &clock)
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let direction = self.player.get_next_move_within(&state, budget);
+        direction as u8
+    }
+
+    pub fn finish_game(&mut self) {
+        self.player.finish_game();
+    }
+
+    // 对战一局结束后调用：根据玩家这一局的表现和 AI 的得分调整 AI 下一局的强度
+    pub fn record_race(&mut self, player: &Game2048, ai_score: u32) {
+        let result = PlayerGame {
+            score: player.score(),
+            max_tile: bitboard::exponent_to_value(player.board().max_exponent()),
+            error_rate: player.stats().error_rate,
+            move_speed: player.stats().move_speed,
+        };
+        self.calibrator.record_game(result, ai_score);
+        self.calibrator.apply(&mut self.player);
+    }
+}
+
+impl AIOpponent {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        // 旧存档只有 AI 本身，校准从初始强度重新开始
+        let Some(rest) = bytes.strip_prefix(&SAVE_MAGIC) else {
+            return AIPlayer::load_state(bytes).map(Self::from_player);
+        };
+        let (len, rest) = rest.split_at_checked(4).ok_or("Truncated AI opponent save")?;
+        let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        let (player, calibrator) = rest.split_at_checked(len).ok_or("Truncated AI opponent save")?;
+        Ok(Self {
+            player: AIPlayer::load_state(player)?,
+            calibrator: SkillCalibrator::from_bytes(calibrator)?,
+        })
+    }
+
+    pub fn with_history(player: AIPlayer, games: Vec<PlayerGame>) -> Self {
+        Self::calibrated(player, SkillCalibrator::from_history(games, CALIBRATION_BAND))
+    }
+
+    fn from_player(player: AIPlayer) -> Self {
+        Self::calibrated(player, SkillCalibrator::new(CALIBRATION_BAND))
+    }
+
+    // 开局前先按校准的强度削弱 AI，第一局就不是满强度
+    fn calibrated(mut player: AIPlayer, calibrator: SkillCalibrator) -> Self {
+        calibrator.apply(&mut player);
+        Self { player, calibrator }
+    }
+}
+
+fn ai_level(level: u8) -> Result<AILevel, JsValue> {
+    match level {
+        0 => Ok(AILevel::Easy),
+        1 => Ok(AILevel::Medium),
+        2 => Ok(AILevel::Hard),
+        3 => Ok(AILevel::Adaptive),
+        _ => Err(JsValue::from_str(&format!("Unknown AI level {}", level))),
+    }
+}
@@ -0,0 +1,201 @@
+#[cfg(feature = "simd")]
+use std::simd::u32x4;
+use std::sync::OnceLock;
+
+// 64位压缩棋盘：每格4位存放方块指数（0为空，1为2，2为4 ...），
+// 第0行位于最低16位，每行内第0列位于最低4位
+pub type Board = u64;
+
+const ROW_MASK: Board = 0xFFFF;
+const COL_MASK: Board = 0x000F_000F_000F_000F;
+
+// 4位能表示的最大指数，达到后不再合并
+pub const MAX_EXPONENT: u32 = 15;
+
+struct MoveTables {
+    row_left: Vec<u16>,
+    row_right: Vec<u16>,
+    col_up: Vec<Board>,
+    col_down: Vec<Board>,
+    score: Vec<u32>,
+}
+
+#[cfg(feature = "simd")]
+use merge_row_simd as merge_row;
+#[cfg(not(feature = "simd"))]
+use merge_row_scalar as merge_row;
+
+static TABLES: OnceLock<MoveTables> = OnceLock::new();
+
+fn tables() -> &'static MoveTables {
+    TABLES.get_or_init(build_tables)
+}
+
+fn build_tables() -> MoveTables {
+    let mut row_left = vec![0u16; 65536];
+    let mut row_right = vec![0u16; 65536];
+    let mut col_up = vec![0; 65536];
+    let mut col_down = vec![0; 65536];
+    let mut score = vec![0u32; 65536];
+
+    for row in 0..65536usize {
+        let cells = decode_row(row as u16);
+        let (merged, gained) = merge_row(cells);
+        let left = encode_row(merged);
+        let right = reverse_row(encode_row(merge_row(decode_row(reverse_row(row as u16))).0));
+
+        // 表中存放的是与原行的异或差值，方便直接作用到整块棋盘上
+        row_left[row] = (row as u16) ^ left;
+        row_right[row] = (row as u16) ^ right;
+        col_up[row] = unpack_col(row as u16) ^ unpack_col(left);
+        col_down[row] = unpack_col(row as u16) ^ unpack_col(right);
+        score[row] = gained;
+    }
+
+    MoveTables { row_left, row_right, col_up, col_down, score }
+}
+
+// 把一行中的方块向左压缩并合并，返回新行和得分
+#[cfg(feature = "simd")]
+pub fn merge_row_simd(row: [u32; 4]) -> ([u32; 4], u32) {
+    let v = u32x4::from_array(row);
+
+    // 压缩非零元素
+    let mut compressed = [0u32; 4];
+    let mut idx = 0;
+
+    for i in 0..4 {
+        if v[i] != 0 {
+            compressed[idx] = v[i];
+            idx += 1;
+        }
+    }
+
+    // 合并相同数字
+    let mut result = u32x4::splat(0);
+    let mut gained = 0;
+    let mut pos = 0;
+
+    let mut i = 0;
+    while i < idx {
+        if i + 1 < idx && compressed[i] == compressed[i + 1] && compressed[i] < 1 << MAX_EXPONENT {
+            result[pos] = compressed[i] * 2;
+            gained += result[pos];
+            i += 2;
+        } else {
+            result[pos] = compressed[i];
+            i += 1;
+        }
+        pos += 1;
+    }
+
+    (result.to_array(), gained)
+}
+
+// 标量路径直接使用规则库的单行合并，simd 路径必须与它逐位一致
+#[cfg(any(test, not(feature = "simd")))]
+pub fn merge_row_scalar(row: [u32; 4]) -> ([u32; 4], u32) {
+    let mut line = row.map(|value| value_to_exponent(value) as u8);
+    let gained = game_2048_core::slide_line(&mut line, MAX_EXPONENT as u8);
+    (line.map(|exponent| exponent_to_value(exponent as u32)), gained)
+}
+
+fn decode_row(row: u16) -> [u32; 4] {
+    let mut cells = [0u32; 4];
+    for (i, cell) in cells.iter_mut().enumerate() {
+        *cell = exponent_to_value(((row >> (4 * i)) & 0xF) as u32);
+    }
+    cells
+}
+
+fn encode_row(cells: [u32; 4]) -> u16 {
+    cells.iter()
+        .enumerate()
+        .fold(0, |row, (i, &value)| row | ((value_to_exponent(value) as u16) << (4 * i)))
+}
+
+fn reverse_row(row: u16) -> u16 {
+    (row >> 12) | ((row >> 4) & 0x00F0) | ((row << 4) & 0x0F00) | (row << 12)
+}
+
+// 把一行的4个半字节展开到一列上（每个半字节间隔16位）
+fn unpack_col(row: u16) -> Board {
+    let tmp = row as Board;
+    (tmp | (tmp << 12) | (tmp << 24) | (tmp << 36)) & COL_MASK
+}
+
+pub use game_2048_core::{exponent_to_value, value_to_exponent};
+
+pub fn transpose(board: Board) -> Board {
+    let a1 = board & 0xF0F0_0F0F_F0F0_0F0F;
+    let a2 = board & 0x0000_F0F0_0000_F0F0;
+    let a3 = board & 0x0F0F_0000_0F0F_0000;
+    let a = a1 | (a2 << 12) | (a3 >> 12);
+    let b1 = a & 0xFF00_FF00_00FF_00FF;
+    let b2 = a & 0x00FF_00FF_0000_0000;
+    let b3 = a & 0x0000_0000_FF00_FF00;
+    b1 | (b2 >> 24) | (b3 << 24)
+}
+
+pub fn get_tile(board: Board, row: usize, col: usize) -> u32 {
+    ((board >> (16 * row + 4 * col)) & 0xF) as u32
+}
+
+pub fn set_tile(board: Board, row: usize, col: usize, exponent: u32) -> Board {
+    let shift = 16 * row + 4 * col;
+    (board & !(0xF << shift)) | ((exponent as Board & 0xF) << shift)
+}
+
+pub fn empty_cells(board: Board) -> Vec<(usize, usize)> {
+    (0..4)
+        .flat_map(|i| (0..4).map(move |j| (i, j)))
+        .filter(|&(i, j)| get_tile(board, i, j) == 0)
+        .collect()
+}
+
+pub fn max_exponent(board: Board) -> u32 {
+    (0..16).map(|k| ((board >> (4 * k)) & 0xF) as u32).max().unwrap_or(0)
+}
+
+// 方向编码与 move_tiles 保持一致：0上 1右 2下 3左
+pub fn execute_move(board: Board, direction: u8) -> (Board, u32) {
+    let t = tables();
+    let mut result = board;
+    let mut gained = 0;
+
+    match direction {
+        0 | 2 => {
+            let col_table = if direction == 0 { &t.col_up } else { &t.col_down };
+            let transposed = transpose(board);
+            for col in 0..4 {
+                let line = ((transposed >> (16 * col)) & ROW_MASK) as usize;
+                result ^= col_table[line] << (4 * col);
+                gained += t.score[line];
+            }
+        }
+        1 | 3 => {
+            let row_table = if direction == 3 { &t.row_left } else { &t.row_right };
+            for row in 0..4 {
+                let line = ((board >> (16 * row)) & ROW_MASK) as usize;
+                result ^= (row_table[line] as Board) << (16 * row);
+                gained += t.score[line];
+            }
+        }
+        _ => {}
+    }
+
+    (result, gained)
+}
+
+#[cfg(all(test, feature = "simd"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simd_and_scalar_merge_every_row_identically() {
+        for row in 0..=u16::MAX {
+            let cells = decode_row(row);
+            assert_eq!(merge_row_simd(cells), merge_row_scalar(cells), "row {:04x}", row);
+        }
+    }
+}
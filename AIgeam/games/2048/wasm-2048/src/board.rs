@@ -0,0 +1,151 @@
+use game_2048_core::{self as rules, Grid, LineStep, MAX_GRID_EXPONENT};
+
+use crate::bitboard;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TileStep {
+    Slide {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+    Merge {
+        first: (usize, usize),
+        second: (usize, usize),
+        to: (usize, usize),
+        exponent: u32,
+    },
+}
+
+// 对外统一的棋盘类型：经典 4x4 使用压缩的 bitboard，其余尺寸使用通用网格
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Board {
+    Packed(bitboard::Board),
+    Grid(Grid),
+}
+
+impl Board {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        if rows == 4 && cols == 4 {
+            Board::Packed(0)
+        } else {
+            Board::Grid(Grid::new(rows, cols))
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        match self {
+            Board::Packed(_) => 4,
+            Board::Grid(grid) => grid.rows(),
+        }
+    }
+
+    pub fn cols(&self) -> usize {
+        match self {
+            Board::Packed(_) => 4,
+            Board::Grid(grid) => grid.cols(),
+        }
+    }
+
+    pub fn get_tile(&self, row: usize, col: usize) -> u32 {
+        match self {
+            Board::Packed(board) => bitboard::get_tile(*board, row, col),
+            Board::Grid(grid) => grid.get(row, col) as u32,
+        }
+    }
+
+    pub fn set_tile(&mut self, row: usize, col: usize, exponent: u32) {
+        match self {
+            Board::Packed(board) => *board = bitboard::set_tile(*board, row, col, exponent),
+            Board::Grid(grid) => grid.set(row, col, exponent as u8),
+        }
+    }
+
+    pub fn execute_move(&self, direction: u8) -> (Board, u32) {
+        match self {
+            Board::Packed(board) => {
+                let (moved, gained) = bitboard::execute_move(*board, direction);
+                (Board::Packed(moved), gained)
+            }
+            Board::Grid(grid) => {
+                let (moved, gained) = grid.execute_move(direction);
+                (Board::Grid(moved), gained)
+            }
+        }
+    }
+
+    // 这种存储方式能表示的最大指数，合并到此为止
+    pub fn exponent_limit(&self) -> u32 {
+        match self {
+            Board::Packed(_) => bitboard::MAX_EXPONENT,
+            Board::Grid(_) => MAX_GRID_EXPONENT as u32,
+        }
+    }
+
+    // 追踪移动中每个方块的去向，返回 (起点, 终点) 坐标形式的步骤，均在移动前的棋盘上计算
+    pub fn trace_move(&self, direction: u8) -> Vec<TileStep> {
+        let (rows, cols) = (self.rows(), self.cols());
+        let (lines, length) = match rules::line_layout(rows, cols, direction) {
+            Some(layout) => layout,
+            None => return Vec::new(),
+        };
+        let max_exponent = self.exponent_limit() as u8;
+        let position = |l: usize, k: usize| rules::line_position(rows, cols, direction, l, k);
+
+        let mut steps = Vec::new();
+        for l in 0..lines {
+            let line: Vec<u8> = (0..length)
+                .map(|k| {
+                    let (i, j) = position(l, k);
+                    self.get_tile(i, j) as u8
+                })
+                .collect();
+            for step in rules::trace_line(&line, max_exponent) {
+                steps.push(match step {
+                    LineStep::Slide { from, to } => TileStep::Slide {
+                        from: position(l, from),
+                        to: position(l, to),
+                    },
+                    LineStep::Merge { first, second, to, exponent } => TileStep::Merge {
+                        first: position(l, first),
+                        second: position(l, second),
+                        to: position(l, to),
+                        exponent: exponent as u32,
+                    },
+                });
+            }
+        }
+        steps
+    }
+
+    pub fn can_move(&self, direction: u8) -> bool {
+        self.execute_move(direction).0 != *self
+    }
+
+    pub fn has_legal_move(&self) -> bool {
+        (0..4).any(|direction| self.can_move(direction))
+    }
+
+    pub fn empty_cells(&self) -> Vec<(usize, usize)> {
+        match self {
+            Board::Packed(board) => bitboard::empty_cells(*board),
+            Board::Grid(grid) => grid.empty_cells(),
+        }
+    }
+
+    pub fn max_exponent(&self) -> u32 {
+        match self {
+            Board::Packed(board) => bitboard::max_exponent(*board),
+            Board::Grid(grid) => grid.max_exponent() as u32,
+        }
+    }
+
+    pub fn to_grid(&self) -> Vec<Vec<u32>> {
+        (0..self.rows())
+            .map(|i| {
+                (0..self.cols())
+                    .map(|j| bitboard::exponent_to_value(self.get_tile(i, j)))
+                    .collect()
+            })
+            .collect()
+    }
+}
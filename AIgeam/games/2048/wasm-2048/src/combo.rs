@@ -0,0 +1,68 @@
+use serde::{Serialize, Deserialize};
+
+// 一步多次合并时每次额外连击数的上限
+pub const MAX_MULTI_MERGE_BONUS: u32 = 10;
+
+// 连击规则：连续发生合并的移动组成一条连击链，没有合并的移动使连击归零
+// 一步中有多次合并时，除第一次外每次合并额外增加 multi_merge_bonus 的连击数
+// multipliers 为 (连击数, 得分百分比)，取满足条件的最大一档，例如 (4, 125) 表示连击 4 及以上得分 x1.25
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ComboConfig {
+    pub multi_merge_bonus: u32,
+    pub multipliers: Vec<(u32, u32)>,
+}
+
+// 默认只统计连击数（道具奖励会用到），得分不加成，保持经典计分；
+// 需要连击加成的模式显式使用 arcade() 或在配置里给出倍率
+impl Default for ComboConfig {
+    fn default() -> Self {
+        Self {
+            multi_merge_bonus: 1,
+            multipliers: Vec::new(),
+        }
+    }
+}
+
+impl ComboConfig {
+    // 街机计分：连击 2、4、6、10 起得分分别 x1.1、x1.25、x1.5、x2
+    pub fn arcade() -> Self {
+        Self {
+            multipliers: vec![(2, 110), (4, 125), (6, 150), (10, 200)],
+            ..Self::default()
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.multi_merge_bonus > MAX_MULTI_MERGE_BONUS {
+            return Err(format!(
+                "Multi-merge bonus {} exceeds the maximum of {}",
+                self.multi_merge_bonus, MAX_MULTI_MERGE_BONUS
+            ));
+        }
+        if self.multipliers.iter().any(|&(_, percent)| percent == 0) {
+            return Err("Combo multipliers must be positive percentages".to_string());
+        }
+        Ok(())
+    }
+
+    // 一步移动之后的连击数，merges 为这一步的合并次数；连击数到 u32::MAX 为止不再增加
+    pub fn extend(&self, combo: u32, merges: u32) -> u32 {
+        if merges == 0 {
+            0
+        } else {
+            combo.saturating_add(1).saturating_add((merges - 1).saturating_mul(self.multi_merge_bonus))
+        }
+    }
+
+    // 连击数对应的得分百分比，没有达到任何一档时为 100
+    pub fn multiplier_percent(&self, combo: u32) -> u32 {
+        self.multipliers.iter()
+            .filter(|&&(threshold, _)| combo >= threshold)
+            .max_by_key(|&&(threshold, _)| threshold)
+            .map_or(100, |&(_, percent)| percent)
+    }
+
+    pub fn apply(&self, gained: u32, combo: u32) -> u32 {
+        (gained as u64 * self.multiplier_percent(combo) as u64 / 100) as u32
+    }
+}
@@ -0,0 +1,78 @@
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+use game_2048_core::CLASSIC_SPAWN_WEIGHTS;
+
+use crate::combo::ComboConfig;
+use crate::power_ups::PowerUpConfig;
+
+pub const MIN_SIZE: usize = 3;
+pub const MAX_SIZE: usize = 8;
+
+// 棋盘尺寸、胜利目标和新方块的生成概率
+// spawn_weights[i] 是生成数值 2^(i+1) 的权重，经典规则为 [0.9, 0.1]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GameConfig {
+    pub rows: usize,
+    pub cols: usize,
+    pub win_tile: u32,
+    pub spawn_weights: Vec<f32>,
+    // 连击计分规则，策划可以在配置里调整
+    #[serde(default)]
+    pub combo: ComboConfig,
+    // 道具模式的发放规则，None 为经典模式（不发放道具，可以自由撤销）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power_ups: Option<PowerUpConfig>,
+    // 对战模式使用：新方块的位置在全部格子中抽取，被占用时顺延到其后第一个空格；
+    // 每次生成消耗的随机数与棋盘无关，双方棋盘被阻碍方块打乱后仍然拿到同一个生成序列
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub shared_spawns: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            rows: 4,
+            cols: 4,
+            win_tile: 2048,
+            spawn_weights: CLASSIC_SPAWN_WEIGHTS.to_vec(),
+            combo: ComboConfig::default(),
+            power_ups: None,
+            shared_spawns: false,
+        }
+    }
+}
+
+impl GameConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_SIZE..=MAX_SIZE).contains(&self.rows) || !(MIN_SIZE..=MAX_SIZE).contains(&self.cols) {
+            return Err(format!(
+                "Board size {}x{} is outside {}x{}..{}x{}",
+                self.rows, self.cols, MIN_SIZE, MIN_SIZE, MAX_SIZE, MAX_SIZE
+            ));
+        }
+        if !self.win_tile.is_power_of_two() || self.win_tile < 8 {
+            return Err(format!("Win tile {} must be a power of two >= 8", self.win_tile));
+        }
+        // 4x4 棋盘使用 4 位指数存储，最大只能表示 32768
+        if self.rows == 4 && self.cols == 4 && self.win_tile > 1 << 15 {
+            return Err(format!("Win tile {} is too large for a 4x4 board", self.win_tile));
+        }
+        if self.spawn_weights.is_empty()
+            || self.spawn_weights.len() > 4
+            || self.spawn_weights.iter().any(|w| !w.is_finite() || *w < 0.0)
+            || self.spawn_weights.iter().sum::<f32>() <= 0.0
+        {
+            return Err("Spawn weights must be 1 to 4 non-negative values with a positive sum".to_string());
+        }
+        self.combo.validate()
+    }
+
+    // 按权重挑选新方块的指数
+    pub fn spawn_exponent(&self, rng: &mut impl Rng) -> u32 {
+        game_2048_core::spawn_exponent(&self.spawn_weights, rng)
+    }
+
+    pub fn win_exponent(&self) -> u32 {
+        self.win_tile.trailing_zeros()
+    }
+}
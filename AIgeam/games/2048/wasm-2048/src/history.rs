@@ -0,0 +1,60 @@
+use std::collections::VecDeque;
+
+pub const DEFAULT_HISTORY_LIMIT: usize = 64;
+
+// 有上限的撤销/重做栈，超出上限时丢弃最早的记录
+#[derive(Clone)]
+pub struct History<T> {
+    undo_stack: VecDeque<T>,
+    redo_stack: Vec<T>,
+    limit: usize,
+}
+
+impl<T> History<T> {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::with_capacity(limit),
+            redo_stack: Vec::new(),
+            limit,
+        }
+    }
+
+    // 记录一个新的历史状态，新操作会使重做栈失效
+    pub fn push(&mut self, entry: T) {
+        self.redo_stack.clear();
+        if self.limit == 0 {
+            return;
+        }
+        if self.undo_stack.len() == self.limit {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(entry);
+    }
+
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo_stack.pop_back()?;
+        self.redo_stack.push(current);
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo_stack.pop()?;
+        self.undo_stack.push_back(current);
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        while self.undo_stack.len() > limit {
+            self.undo_stack.pop_front();
+        }
+    }
+}
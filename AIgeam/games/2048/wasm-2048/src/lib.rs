@@ -1,170 +1,624 @@
-#![cfg(target_feature = "simd128")]
+// 开启 simd feature（需要 nightly）时用 std::simd 计算行合并，否则使用标量实现，两条路径结果逐位一致；
+// 两种构建都可以在非 wasm 平台上使用，供服务端和测试程序直接调用引擎
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 use wasm_bindgen::prelude::*;
 use serde::{Serialize, Deserialize};
-use std::simd::{u32x4, mask32x4};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+use game_2048::ai_player::{AILevel, AIPlayer, Handicap};
+
+mod ai;
+mod bitboard;
+mod board;
+mod combo;
+mod config;
+mod history;
+mod outcome;
+mod power_ups;
+mod puzzle;
+mod replay;
+mod telemetry;
+mod tiles;
+mod time_attack;
+
+pub use ai::AIOpponent;
+pub use board::{Board, TileStep};
+pub use combo::ComboConfig;
+pub use config::GameConfig;
+use history::{History, DEFAULT_HISTORY_LIMIT};
+pub use outcome::{GameStatus, Merge, MoveResult};
+pub use power_ups::{PowerUp, PowerUpConfig, PowerUpState};
+pub use puzzle::{Puzzle, PuzzleDefinition, PuzzleGoal, PuzzleStatus};
+pub use replay::{Replay, ReplayEvent, SpawnedTile};
+pub use telemetry::Telemetry;
+pub use tiles::{Cell, TileTransition};
+pub use time_attack::{TimeAttack, TimeAttackConfig, TimeAttackResult};
+use tiles::TileTracker;
 
 #[derive(Serialize, Deserialize)]
 pub struct GameState {
-    pub grid: [[u32; 4]; 4],
+    pub grid: Vec<Vec<u32>>,
+    pub tile_ids: Vec<Vec<u32>>,
     pub score: u32,
     pub stats: GameStats,
 }
 
-#[derive(Serialize, Deserialize)]
+// move_speed 为最近一段时间每秒的有效移动次数，error_rate 为无效方向所占的比例，
+// hesitation_rate、think_time_ms、pattern_break_rate 同样是最近一段时间的滚动统计
+#[derive(Serialize, Deserialize, Clone)]
 pub struct GameStats {
     pub move_speed: f32,
     pub error_rate: f32,
     pub combo: u32,
+    // 本局最长的连击
+    #[serde(default)]
+    pub longest_combo: u32,
+    #[serde(default)]
+    pub hesitation_rate: f32,
+    #[serde(default)]
+    pub think_time_ms: f32,
+    #[serde(default)]
+    pub pattern_break_rate: f32,
 }
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Game2048 {
-    state: GameState,
-    rng: rand::rngs::ThreadRng,
+    board: Board,
+    tiles: TileTracker,
+    score: u32,
+    stats: GameStats,
+    rng: ChaCha8Rng,
+    status: GameStatus,
+    config: GameConfig,
+    replay: Replay,
+    history: History<Snapshot>,
+    // 指定开局时按顺序生成的方块，用完后不再生成新方块；None 表示按种子随机生成
+    spawn_script: Option<VecDeque<SpawnedTile>>,
+    // 道具背包和生效中的效果，不随撤销回退，避免用撤销刷道具
+    power_ups: PowerUpState,
+    // 玩家的输入统计，撤销不会抹掉已经发生的输入
+    telemetry: Telemetry,
+    // 提示和复盘用的 AI，第一次分析时创建，之后复用
+    analyst: Option<AIPlayer>,
+}
+
+// 撤销所需的完整状态，包含随机数发生器，保证撤销后重做得到同样的新方块
+#[derive(Clone)]
+struct Snapshot {
+    board: Board,
+    tiles: TileTracker,
+    score: u32,
+    stats: GameStats,
+    rng: ChaCha8Rng,
+    status: GameStatus,
+    spawn_script: Option<VecDeque<SpawnedTile>>,
 }
 
 #[wasm_bindgen]
 impl Game2048 {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
-        let mut game = Self {
-            state: GameState {
-                grid: [[0; 4]; 4],
-                score: 0,
-                stats: GameStats {
-                    move_speed: 0.0,
-                    error_rate: 0.0,
-                    combo: 0,
-                }
-            },
-            rng: rand::thread_rng(),
+        Self::with_seed(rand::random())
+    }
+
+    // 固定种子开局，相同种子和相同操作序列必然得到相同的对局
+    pub fn with_seed(seed: u64) -> Self {
+        Self::create(GameConfig::default(), seed)
+    }
+
+    // 道具模式：按默认规则发放道具并使用街机连击计分，撤销只能通过 UndoLastMove 道具进行；
+    // 没有倒计时，不发放冻结时间（限时模式见 TimeAttack::with_power_ups）
+    pub fn with_power_ups(seed: u64) -> Self {
+        let config = GameConfig {
+            combo: ComboConfig::arcade(),
+            power_ups: Some(PowerUpConfig::untimed()),
+            ..GameConfig::default()
         };
-        game.spawn_tile();
-        game.spawn_tile();
-        game
+        Self::create(config, seed)
+    }
+
+    // 经典规则加上街机连击计分
+    pub fn with_combos(seed: u64) -> Self {
+        let config = GameConfig { combo: ComboConfig::arcade(), ..GameConfig::default() };
+        Self::create(config, seed)
+    }
+
+    pub fn with_config(rows: usize, cols: usize, win_tile: u32, spawn_weights: Vec<f32>) -> Result<Game2048, JsValue> {
+        let config = GameConfig { rows, cols, win_tile, spawn_weights, ..GameConfig::default() };
+        Self::from_config(config, rand::random()).map_err(|e| JsValue::from_str(&e))
+    }
+
+    // 完整的 GameConfig 对象，可以带上连击倍率和道具规则（省略时为经典计分、不发放道具）
+    pub fn with_game_config(config: JsValue, seed: u64) -> Result<Game2048, JsValue> {
+        let config: GameConfig = serde_wasm_bindgen::from_value(config)?;
+        Self::from_config(config, seed).map_err(|e| JsValue::from_str(&e))
+    }
+
+    pub fn rows(&self) -> usize {
+        self.config.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.config.cols
+    }
+
+    pub fn win_tile(&self) -> u32 {
+        self.config.win_tile
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.replay.seed
+    }
+
+    pub fn get_replay(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.replay).unwrap()
+    }
+
+    pub fn from_replay(replay: JsValue) -> Result<Game2048, JsValue> {
+        let replay: Replay = serde_wasm_bindgen::from_value(replay)?;
+        Self::replay(&replay).map_err(|e| JsValue::from_str(&e))
     }
 
     pub fn get_state(&self) -> JsValue {
-        serde_wasm_bindgen::to_value(&self.state).unwrap()
+        serde_wasm_bindgen::to_value(&self.snapshot()).unwrap()
     }
 
-    pub fn move_tiles(&mut self, direction: u8) -> bool {
-        let moved = match direction {
-            0 => self.move_up(),
-            1 => self.move_right(),
-            2 => self.move_down(),
-            3 => self.move_left(),
-            _ => false,
-        };
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
 
-        if moved {
-            self.spawn_tile();
-            self.update_stats();
-        }
-        moved
+    // 提示与复盘：每个方向是否合法、AI 评估的期望分数，以及推荐方向和把握程度
+    pub fn analyze(&mut self) -> JsValue {
+        let cells = self.config.rows * self.config.cols;
+        let analyst = self.analyst.get_or_insert_with(|| {
+            let mut player = AIPlayer::new(AILevel::Hard);
+            // 大于 4x4 的棋盘分支太多，少搜一层，提示才不会卡住界面
+            if cells > 16 {
+                player.set_handicap(Handicap { depth: 2, noise: 0.0 });
+            }
+            player
+        });
+        let analysis = analyst.analyze(self.board.to_grid(), self.score);
+        serde_wasm_bindgen::to_value(&analysis).unwrap()
     }
 
-    fn move_left(&mut self) -> bool {
-        let mut moved = false;
-        for row in &mut self.state.grid {
-            let (new_row, row_moved) = self.merge_row_simd(row);
-            *row = new_row;
-            moved |= row_moved;
-        }
-        moved
+    pub fn move_tiles(&mut self, direction: u8) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.play(direction)).unwrap()
+    }
+
+    // now_ms 为输入发生的时间（例如 performance.now()），用于统计移动速度和犹豫
+    pub fn move_tiles_at(&mut self, direction: u8, now_ms: f64) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.play_at(direction, now_ms)).unwrap()
     }
 
-    fn merge_row_simd(&mut self, row: &[u32; 4]) -> ([u32; 4], bool) {
-        let mut v = u32x4::from_array(*row);
-        let zero = u32x4::splat(0);
-        
-        // 压缩非零元素
-        let mut compressed = u32x4::splat(0);
-        let mut idx = 0;
-        
-        for i in 0..4 {
-            if v[i] != 0 {
-                compressed[idx] = v[i];
-                idx += 1;
+    pub fn undo(&mut self) -> bool {
+        if self.config.power_ups.is_some() {
+            return false;
+        }
+        match self.history.undo(self.save_snapshot()) {
+            Some(previous) => {
+                self.restore_snapshot(previous);
+                self.replay.record(ReplayEvent::Undo);
+                true
             }
+            None => false,
         }
-        
-        // 合并相同数字
-        let mut merged = false;
-        let mut result = u32x4::splat(0);
-        let mut pos = 0;
-        
-        let mut i = 0;
-        while i < idx {
-            if i + 1 < idx && compressed[i] == compressed[i + 1] {
-                result[pos] = compressed[i] * 2;
-                self.state.score += result[pos];
-                merged = true;
-                i += 2;
-            } else {
-                result[pos] = compressed[i];
-                i += 1;
+    }
+
+    pub fn redo(&mut self) -> bool {
+        if self.config.power_ups.is_some() {
+            return false;
+        }
+        match self.history.redo(self.save_snapshot()) {
+            Some(next) => {
+                self.restore_snapshot(next);
+                self.replay.record(ReplayEvent::Redo);
+                true
             }
-            pos += 1;
+            None => false,
         }
-        
-        (result.to_array(), merged)
     }
 
-    fn spawn_tile(&mut self) {
-        let empty_cells: Vec<(usize, usize)> = (0..4)
-            .flat_map(|i| (0..4).map(move |j| (i, j)))
-            .filter(|&(i, j)| self.state.grid[i][j] == 0)
-            .collect();
+    pub fn can_undo(&self) -> bool {
+        self.config.power_ups.is_none() && self.history.can_undo()
+    }
 
-        if let Some(&(i, j)) = empty_cells.choose(&mut self.rng) {
-            self.state.grid[i][j] = if self.rng.gen_bool(0.9) { 2 } else { 4 };
-        }
+    pub fn can_redo(&self) -> bool {
+        self.config.power_ups.is_none() && self.history.can_redo()
+    }
+
+    pub fn get_power_ups(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.power_ups).unwrap()
+    }
+
+    // 使用背包中第 slot 个道具，返回方块的动画事件（打乱棋盘时为每个方块的移动）
+    pub fn activate_power_up(&mut self, slot: usize) -> Result<JsValue, JsValue> {
+        self.use_power_up(slot)
+            .map(|transitions| serde_wasm_bindgen::to_value(&transitions).unwrap())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history.set_limit(limit);
+    }
+
+    fn spawn_tile(&mut self) -> Option<(SpawnedTile, TileTransition)> {
+        let empty_cells = self.board.empty_cells();
+
+        let (i, j, exponent) = match &mut self.spawn_script {
+            Some(script) => {
+                let tile = script.pop_front()?;
+                let target = tile.row as usize * self.config.cols + tile.col as usize;
+                let (i, j) = empty_cell_from(&empty_cells, self.config.rows, self.config.cols, target)?;
+                (i, j, bitboard::value_to_exponent(tile.value))
+            }
+            None if self.config.shared_spawns => {
+                let target = self.rng.gen_range(0..self.config.rows * self.config.cols);
+                let exponent = self.config.spawn_exponent(&mut self.rng);
+                let (i, j) = empty_cell_from(&empty_cells, self.config.rows, self.config.cols, target)?;
+                (i, j, exponent)
+            }
+            None => game_2048_core::choose_spawn(&empty_cells, &self.config.spawn_weights, &mut self.rng)?,
+        };
+        let value = bitboard::exponent_to_value(exponent);
+        self.board.set_tile(i, j, exponent);
+        let transition = self.tiles.spawn(i, j, value);
+        Some((SpawnedTile { row: i as u8, col: j as u8, value }, transition))
     }
 
-    fn update_stats(&mut self) {
+    fn update_stats(&mut self, combo: u32) {
         // 更新游戏统计信息
-        self.state.stats.combo += 1;
-        self.state.stats.move_speed = (self.state.stats.move_speed * 0.9 + 0.1).min(1.0);
+        self.stats.combo = combo;
+        self.stats.longest_combo = self.stats.longest_combo.max(combo);
     }
 }
 
 // 辅助函数
 impl Game2048 {
-    fn rotate_grid(&mut self) {
-        let mut new_grid = [[0; 4]; 4];
-        for i in 0..4 {
-            for j in 0..4 {
-                new_grid[i][j] = self.state.grid[3-j][i];
+    pub fn play(&mut self, direction: u8) -> MoveResult {
+        self.play_input(direction, None)
+    }
+
+    pub fn play_at(&mut self, direction: u8, now_ms: f64) -> MoveResult {
+        self.play_input(direction, Some(now_ms))
+    }
+
+    // 游戏结束后的输入不计入统计
+    fn play_input(&mut self, direction: u8, time_ms: Option<f64>) -> MoveResult {
+        let playing = self.status != GameStatus::Lost;
+        let result = self.execute(direction);
+        if playing {
+            self.telemetry.record(direction, result.moved, time_ms);
+            self.telemetry.write_stats(&mut self.stats);
+        }
+        result
+    }
+
+    fn execute(&mut self, direction: u8) -> MoveResult {
+        let (board, gained) = self.board.execute_move(direction);
+        if self.status == GameStatus::Lost || board == self.board {
+            self.replay.record(ReplayEvent::Move { direction, spawned: None });
+            return MoveResult::rejected(self.status);
+        }
+
+        self.history.push(self.save_snapshot());
+        let steps = self.board.trace_move(direction);
+        let mut transitions = self.tiles.apply_steps(&steps, bitboard::exponent_to_value);
+        let merges: Vec<Merge> = transitions.iter()
+            .filter_map(|transition| match *transition {
+                TileTransition::Merge { at, value, .. } => Some(Merge { row: at.row, col: at.col, value }),
+                _ => None,
+            })
+            .collect();
+
+        // 先延续连击，再按新的连击数和道具效果计算这一步的得分
+        let previous_combo = self.stats.combo;
+        let combo = self.config.combo.extend(previous_combo, merges.len() as u32);
+        let gained = self.config.combo.apply(gained, combo) * self.power_ups.score_multiplier();
+
+        self.board = board;
+        self.score += gained;
+        let spawned = self.spawn_tile().map(|(tile, transition)| {
+            transitions.push(transition);
+            tile
+        });
+        self.update_stats(combo);
+        let power_ups_granted = match &self.config.power_ups {
+            Some(config) => {
+                let max_tile = bitboard::exponent_to_value(self.board.max_exponent());
+                self.power_ups.after_move(config, previous_combo, combo, max_tile)
+            }
+            None => Vec::new(),
+        };
+        self.update_status();
+        self.replay.record(ReplayEvent::Move { direction, spawned });
+
+        MoveResult {
+            moved: true,
+            merges,
+            transitions,
+            score_delta: gained,
+            combo,
+            spawned,
+            status: self.status,
+            power_ups_granted,
+        }
+    }
+
+    // 无路可走时也可以使用道具（例如打乱棋盘），使用后重新判断状态
+    pub fn use_power_up(&mut self, slot: usize) -> Result<Vec<TileTransition>, String> {
+        let power_up = *self.power_ups.inventory.get(slot).ok_or("No power-up in that slot")?;
+        if power_up == PowerUp::UndoLastMove && !self.history.can_undo() {
+            return Err("There is no move to undo".to_string());
+        }
+        self.power_ups.take(slot);
+
+        let transitions = match power_up {
+            PowerUp::UndoLastMove => {
+                if let Some(previous) = self.history.undo(self.save_snapshot()) {
+                    self.restore_snapshot(previous);
+                }
+                Vec::new()
             }
+            PowerUp::ShuffleBoard => self.shuffle_board(),
+            PowerUp::DoublePoints { .. } | PowerUp::FreezeTime { .. } => Vec::new(),
+        };
+        self.update_status();
+        self.replay.record(ReplayEvent::PowerUp { power_up });
+        Ok(transitions)
+    }
+
+    // 对战模式由服务端调用：在指定的空格放入对手送来的方块，放入后可能导致无路可走
+    pub fn place_blocker(&mut self, tile: SpawnedTile) -> Result<TileTransition, String> {
+        let (i, j) = (tile.row as usize, tile.col as usize);
+        if self.status == GameStatus::Lost {
+            return Err("Game is already over".to_string());
+        }
+        if i >= self.config.rows || j >= self.config.cols || self.board.get_tile(i, j) != 0 {
+            return Err(format!("Cell ({}, {}) is not an empty cell", i, j));
         }
-        self.state.grid = new_grid;
+        if tile.value < 2 || !tile.value.is_power_of_two()
+            || bitboard::value_to_exponent(tile.value) > self.board.exponent_limit()
+        {
+            return Err(format!("Invalid blocker value {}", tile.value));
+        }
+        self.board.set_tile(i, j, bitboard::value_to_exponent(tile.value));
+        let transition = self.tiles.spawn(i, j, tile.value);
+        self.update_status();
+        self.replay.record(ReplayEvent::Blocker { tile });
+        Ok(transition)
     }
 
-    fn move_up(&mut self) -> bool {
-        self.rotate_grid();
-        let moved = self.move_left();
-        self.rotate_grid();
-        self.rotate_grid();
-        self.rotate_grid();
-        moved
+    // 计时模式调用：先用冻结时间抵扣流逝的毫秒数，返回需要计入倒计时的部分
+    pub fn consume_freeze(&mut self, elapsed: f64) -> f64 {
+        self.power_ups.consume_freeze(elapsed)
     }
 
-    fn move_right(&mut self) -> bool {
-        self.rotate_grid();
-        self.rotate_grid();
-        let moved = self.move_left();
-        self.rotate_grid();
-        self.rotate_grid();
-        moved
+    pub fn power_ups(&self) -> &PowerUpState {
+        &self.power_ups
     }
 
-    fn move_down(&mut self) -> bool {
-        self.rotate_grid();
-        self.rotate_grid();
-        self.rotate_grid();
-        let moved = self.move_left();
-        self.rotate_grid();
-        moved
+    // 把现有方块随机放到棋盘的各个格子上，方块 id 跟着移动
+    fn shuffle_board(&mut self) -> Vec<TileTransition> {
+        let (rows, cols) = (self.config.rows, self.config.cols);
+        let mut cells: Vec<(usize, usize)> = (0..rows).flat_map(|i| (0..cols).map(move |j| (i, j))).collect();
+        let occupied: Vec<(usize, usize)> = cells.iter()
+            .copied()
+            .filter(|&(i, j)| self.board.get_tile(i, j) != 0)
+            .collect();
+        cells.shuffle(&mut self.rng);
+
+        let mut board = Board::new(rows, cols);
+        let steps: Vec<TileStep> = occupied.iter()
+            .zip(cells.iter())
+            .map(|(&from, &to)| {
+                board.set_tile(to.0, to.1, self.board.get_tile(from.0, from.1));
+                TileStep::Slide { from, to }
+            })
+            .collect();
+        self.board = board;
+        self.tiles.apply_steps(&steps, bitboard::exponent_to_value)
+    }
+
+    // 达到目标方块的那一步报告 Won，之后继续游戏则为 ContinuedAfterWin
+    fn update_status(&mut self) {
+        self.status = game_2048_core::next_status(
+            self.status.into(),
+            self.board.has_legal_move(),
+            self.board.max_exponent(),
+            self.config.win_exponent(),
+        ).into();
+    }
+
+    fn create(config: GameConfig, seed: u64) -> Self {
+        let mut game = Self::create_empty(config, seed);
+        for _ in 0..2 {
+            if let Some((tile, _)) = game.spawn_tile() {
+                game.replay.initial_tiles.push(tile);
+            }
+        }
+        game
     }
-} 
\ No newline at end of file
+
+    fn create_empty(config: GameConfig, seed: u64) -> Self {
+        Self {
+            board: Board::new(config.rows, config.cols),
+            tiles: TileTracker::new(config.rows, config.cols),
+            score: 0,
+            stats: GameStats {
+                move_speed: 0.0,
+                error_rate: 0.0,
+                combo: 0,
+                longest_combo: 0,
+                hesitation_rate: 0.0,
+                think_time_ms: 0.0,
+                pattern_break_rate: 0.0,
+            },
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            status: GameStatus::Playing,
+            replay: Replay::new(seed, config.clone()),
+            config,
+            history: History::new(DEFAULT_HISTORY_LIMIT),
+            spawn_script: None,
+            power_ups: PowerUpState::default(),
+            telemetry: Telemetry::default(),
+            analyst: None,
+        }
+    }
+
+    pub fn from_config(config: GameConfig, seed: u64) -> Result<Self, String> {
+        config.validate()?;
+        Ok(Self::create(config, seed))
+    }
+
+    // 指定开局：grid 为初始方块数值（0 为空格），之后按 spawns 的顺序生成方块
+    // 预定位置已被占用时改放在其后第一个空格（按行优先、循环查找）
+    pub fn from_position(config: GameConfig, grid: &[Vec<u32>], spawns: Vec<SpawnedTile>) -> Result<Self, String> {
+        config.validate()?;
+        if grid.len() != config.rows || grid.iter().any(|row| row.len() != config.cols) {
+            return Err(format!("Initial grid must be {}x{}", config.rows, config.cols));
+        }
+        let max_exponent = Board::new(config.rows, config.cols).exponent_limit();
+        let valid_value = |value: u32| {
+            value == 0 || (value >= 2 && value.is_power_of_two() && bitboard::value_to_exponent(value) <= max_exponent)
+        };
+        if grid.iter().flatten().any(|&value| !valid_value(value)) {
+            return Err("Initial grid contains an invalid tile value".to_string());
+        }
+        if spawns.iter().any(|tile| {
+            tile.row as usize >= config.rows || tile.col as usize >= config.cols || tile.value == 0 || !valid_value(tile.value)
+        }) {
+            return Err("Spawn sequence contains an invalid tile".to_string());
+        }
+
+        let mut game = Self::create_empty(config, 0);
+        for (i, row) in grid.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                if value != 0 {
+                    game.board.set_tile(i, j, bitboard::value_to_exponent(value));
+                    game.tiles.spawn(i, j, value);
+                }
+            }
+        }
+        game.replay.initial_grid = Some(grid.to_vec());
+        game.replay.spawn_script = spawns.clone();
+        game.spawn_script = Some(spawns.into());
+        game.update_status();
+        Ok(game)
+    }
+
+    // 按录像重放整局游戏，并逐步核对生成的方块是否与记录一致
+    pub fn replay(replay: &Replay) -> Result<Self, String> {
+        let mut game = match &replay.initial_grid {
+            Some(grid) => Self::from_position(replay.config.clone(), grid, replay.spawn_script.clone())?,
+            None => Self::from_config(replay.config.clone(), replay.seed)?,
+        };
+        if game.replay.initial_tiles != replay.initial_tiles {
+            return Err("Initial tiles do not match seed".to_string());
+        }
+
+        // 录像里的撤销次数不受本地历史上限约束
+        game.history.set_limit(replay.events.len());
+
+        for (index, event) in replay.events.iter().enumerate() {
+            let applied = match *event {
+                ReplayEvent::Move { direction, .. } => {
+                    game.play(direction);
+                    true
+                }
+                ReplayEvent::Undo => game.undo(),
+                ReplayEvent::Redo => game.redo(),
+                ReplayEvent::PowerUp { power_up } => {
+                    match game.power_ups.inventory.iter().position(|&owned| owned == power_up) {
+                        Some(slot) => game.use_power_up(slot).is_ok(),
+                        None => false,
+                    }
+                }
+                ReplayEvent::Blocker { tile } => game.place_blocker(tile).is_ok(),
+            };
+            if !applied || game.replay.events.last() != Some(event) {
+                return Err(format!("Replay diverged at event {}", index));
+            }
+        }
+        game.history.set_limit(DEFAULT_HISTORY_LIMIT);
+        Ok(game)
+    }
+
+    pub fn replay_log(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    // 每格方块的 id，0 为空格，与 get_state 的 tile_ids 相同
+    pub fn tile_ids(&self) -> Vec<Vec<u32>> {
+        self.tiles.to_grid()
+    }
+
+    pub fn stats(&self) -> &GameStats {
+        &self.stats
+    }
+
+    pub fn telemetry(&self) -> &Telemetry {
+        &self.telemetry
+    }
+
+    fn save_snapshot(&self) -> Snapshot {
+        Snapshot {
+            board: self.board.clone(),
+            tiles: self.tiles.clone(),
+            score: self.score,
+            stats: self.stats.clone(),
+            rng: self.rng.clone(),
+            status: self.status,
+            spawn_script: self.spawn_script.clone(),
+        }
+    }
+
+    fn restore_snapshot(&mut self, snapshot: Snapshot) {
+        self.board = snapshot.board;
+        self.tiles = snapshot.tiles;
+        self.score = snapshot.score;
+        self.stats = snapshot.stats;
+        self.telemetry.write_stats(&mut self.stats);
+        self.rng = snapshot.rng;
+        self.status = snapshot.status;
+        self.spawn_script = snapshot.spawn_script;
+    }
+
+    fn snapshot(&self) -> GameState {
+        GameState {
+            grid: self.board.to_grid(),
+            tile_ids: self.tiles.to_grid(),
+            score: self.score,
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+impl Default for Game2048 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 预定位置为空就用它，否则从它开始按行优先顺序循环查找下一个空格
+fn empty_cell_from(empty_cells: &[(usize, usize)], rows: usize, cols: usize, target: usize) -> Option<(usize, usize)> {
+    let total = rows * cols;
+    empty_cells.iter()
+        .copied()
+        .min_by_key(|&(i, j)| (i * cols + j + total - target) % total)
+}
@@ -0,0 +1,81 @@
+use serde::{Serialize, Deserialize};
+use wasm_bindgen::prelude::*;
+use game_2048_core::Status;
+
+use crate::power_ups::PowerUp;
+use crate::replay::SpawnedTile;
+use crate::tiles::TileTransition;
+
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameStatus {
+    Playing,
+    Won,
+    Lost,
+    ContinuedAfterWin,
+    // 限时模式倒计时归零
+    TimeUp,
+}
+
+// 规则库的状态不包含各模式自己的结束条件（例如 TimeUp），这些由模式自己判断
+impl From<Status> for GameStatus {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Playing => GameStatus::Playing,
+            Status::Won => GameStatus::Won,
+            Status::ContinuedAfterWin => GameStatus::ContinuedAfterWin,
+            Status::Lost => GameStatus::Lost,
+        }
+    }
+}
+
+impl From<GameStatus> for Status {
+    fn from(status: GameStatus) -> Self {
+        match status {
+            GameStatus::Won => Status::Won,
+            GameStatus::ContinuedAfterWin => Status::ContinuedAfterWin,
+            GameStatus::Lost => Status::Lost,
+            GameStatus::Playing | GameStatus::TimeUp => Status::Playing,
+        }
+    }
+}
+
+// 一次 move_tiles 的完整结果，前端据此播放动画而不必再去比对棋盘
+// transitions 按方块 id 描述滑动、合并和新生成的方块
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MoveResult {
+    pub moved: bool,
+    pub merges: Vec<Merge>,
+    pub transitions: Vec<TileTransition>,
+    pub score_delta: u32,
+    // 这一步之后的连击数，没有合并时为 0
+    #[serde(default)]
+    pub combo: u32,
+    pub spawned: Option<SpawnedTile>,
+    pub status: GameStatus,
+    // 这一步获得的道具
+    #[serde(default)]
+    pub power_ups_granted: Vec<PowerUp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Merge {
+    pub row: u8,
+    pub col: u8,
+    pub value: u32,
+}
+
+impl MoveResult {
+    pub fn rejected(status: GameStatus) -> Self {
+        Self {
+            moved: false,
+            merges: Vec::new(),
+            transitions: Vec::new(),
+            score_delta: 0,
+            combo: 0,
+            spawned: None,
+            status,
+            power_ups_granted: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Serialize, Deserialize};
+
+// 道具：DoublePoints 的 duration 为生效的移动步数，FreezeTime 的 duration 为冻结计时的毫秒数
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum PowerUp {
+    DoublePoints { duration: u32 },
+    UndoLastMove,
+    ShuffleBoard,
+    FreezeTime { duration: u32 },
+}
+
+// 道具的发放规则，策划可以直接在配置里调整
+// combo_rewards: 连击数达到 N 时发放（一步跨过多档时每档都发放）；tile_rewards: 每局第一次合成出该方块时发放
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PowerUpConfig {
+    pub combo_rewards: Vec<(u32, PowerUp)>,
+    pub tile_rewards: Vec<(u32, PowerUp)>,
+    pub max_inventory: usize,
+}
+
+impl Default for PowerUpConfig {
+    fn default() -> Self {
+        Self {
+            combo_rewards: vec![
+                (5, PowerUp::DoublePoints { duration: 5 }),
+                (10, PowerUp::ShuffleBoard),
+                (20, PowerUp::FreezeTime { duration: 5_000 }),
+            ],
+            tile_rewards: vec![
+                (256, PowerUp::UndoLastMove),
+                (512, PowerUp::FreezeTime { duration: 10_000 }),
+                (1024, PowerUp::DoublePoints { duration: 10 }),
+            ],
+            max_inventory: 3,
+        }
+    }
+}
+
+impl PowerUpConfig {
+    // 没有倒计时的对局用的发放规则：冻结时间在这类对局里没有作用，不发放
+    pub fn untimed() -> Self {
+        let mut config = Self::default();
+        let useful = |&(_, power_up): &(u32, PowerUp)| !matches!(power_up, PowerUp::FreezeTime { .. });
+        config.combo_rewards.retain(useful);
+        config.tile_rewards.retain(useful);
+        config
+    }
+}
+
+// 一局中的道具状态：背包、正在生效的效果、已经领过奖励的方块，
+// 以及当前连击链上已经领过奖励的最高连击档位（连击中断时清零）
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PowerUpState {
+    pub inventory: Vec<PowerUp>,
+    pub double_points_moves: u32,
+    pub freeze_ms: f64,
+    pub rewarded_tiles: Vec<u32>,
+    #[serde(default)]
+    pub rewarded_combo: u32,
+}
+
+impl PowerUpState {
+    // 一步有效移动之后调用：先消耗双倍得分的剩余步数，再按连击数和最大方块发放道具
+    // previous_combo 为这一步之前的连击数，背包满了的道具直接作废
+    pub fn after_move(&mut self, config: &PowerUpConfig, previous_combo: u32, combo: u32, max_tile: u32) -> Vec<PowerUp> {
+        self.double_points_moves = self.double_points_moves.saturating_sub(1);
+
+        // 撤销会把连击数退回去，但已领过的档位不随之回退，重走同一段连击不会再次领奖
+        if combo == 0 {
+            self.rewarded_combo = 0;
+        }
+        let mut granted = Vec::new();
+        for &(threshold, power_up) in &config.combo_rewards {
+            if previous_combo < threshold && combo >= threshold && threshold > self.rewarded_combo {
+                granted.push(power_up);
+            }
+        }
+        self.rewarded_combo = config.combo_rewards.iter()
+            .map(|&(threshold, _)| threshold)
+            .filter(|&threshold| combo >= threshold)
+            .fold(self.rewarded_combo, u32::max);
+        for &(tile, power_up) in &config.tile_rewards {
+            if max_tile >= tile && !self.rewarded_tiles.contains(&tile) {
+                self.rewarded_tiles.push(tile);
+                granted.push(power_up);
+            }
+        }
+
+        granted.truncate(config.max_inventory.saturating_sub(self.inventory.len()));
+        self.inventory.extend(granted.iter().copied());
+        granted
+    }
+
+    // 这一步得分的倍数，双倍得分生效期间为 2
+    pub fn score_multiplier(&self) -> u32 {
+        if self.double_points_moves > 0 { 2 } else { 1 }
+    }
+
+    // 从背包中取出道具；持续型道具在这里开始生效，其余由调用方处理
+    pub fn take(&mut self, slot: usize) -> Option<PowerUp> {
+        if slot >= self.inventory.len() {
+            return None;
+        }
+        let power_up = self.inventory.remove(slot);
+        match power_up {
+            PowerUp::DoublePoints { duration } => self.double_points_moves += duration,
+            PowerUp::FreezeTime { duration } => self.freeze_ms += duration as f64,
+            PowerUp::UndoLastMove | PowerUp::ShuffleBoard => {}
+        }
+        Some(power_up)
+    }
+
+    // 计时模式流逝了 elapsed 毫秒，先用冻结时间抵扣，返回需要真正计入倒计时的部分
+    pub fn consume_freeze(&mut self, elapsed: f64) -> f64 {
+        let frozen = elapsed.min(self.freeze_ms);
+        self.freeze_ms -= frozen;
+        elapsed - frozen
+    }
+}
@@ -0,0 +1,277 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashSet;
+use wasm_bindgen::prelude::*;
+use game_2048::ai_player::{BoardEvaluator, BoardState, EmptyCells, MergePotential, Monotonicity, WeightedEvaluator};
+
+use crate::config::GameConfig;
+use crate::outcome::MoveResult;
+use crate::replay::SpawnedTile;
+use crate::{bitboard, Game2048};
+
+// 验证谜题时默认最多展开的局面数
+pub const DEFAULT_SEARCH_BUDGET: usize = 200_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PuzzleGoal {
+    // 合成出不小于 value 的方块
+    ReachTile { value: u32 },
+    // 棋盘上剩余的方块不超过 tiles 个
+    ClearTo { tiles: u32 },
+}
+
+// 谜题定义，内容团队以 JSON 发布，例如：
+// {"id": "daily-001", "grid": [[2,2,4,8],...], "spawns": [{"row":0,"col":0,"value":2}],
+//  "move_limit": 12, "goal": {"type": "reach_tile", "value": 64}}
+// spawns 为每一步之后依次生成的方块，用完后不再生成
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PuzzleDefinition {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    pub grid: Vec<Vec<u32>>,
+    #[serde(default)]
+    pub spawns: Vec<SpawnedTile>,
+    pub move_limit: u32,
+    pub goal: PuzzleGoal,
+}
+
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PuzzleStatus {
+    InProgress,
+    Solved,
+    Failed,
+}
+
+impl PuzzleDefinition {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let definition: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        definition.check()?;
+        Ok(definition)
+    }
+
+    // 一个文件里可以放一组谜题（JSON 数组）
+    pub fn list_from_json(json: &str) -> Result<Vec<Self>, String> {
+        let definitions: Vec<Self> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        for definition in &definitions {
+            definition.check().map_err(|e| format!("Puzzle {}: {}", definition.id, e))?;
+        }
+        Ok(definitions)
+    }
+
+    // 格式检查：棋盘、生成序列和目标是否合法，不涉及能否解出
+    pub fn check(&self) -> Result<(), String> {
+        if self.move_limit == 0 {
+            return Err("Move limit must be positive".to_string());
+        }
+        if let PuzzleGoal::ClearTo { tiles: 0 } = self.goal {
+            return Err("Clear goal must leave at least one tile".to_string());
+        }
+        self.new_game().map(|_| ())
+    }
+
+    pub fn config(&self) -> GameConfig {
+        GameConfig {
+            rows: self.grid.len(),
+            cols: self.grid.first().map_or(0, |row| row.len()),
+            win_tile: match self.goal {
+                PuzzleGoal::ReachTile { value } => value,
+                PuzzleGoal::ClearTo { .. } => GameConfig::default().win_tile,
+            },
+            ..GameConfig::default()
+        }
+    }
+
+    pub fn new_game(&self) -> Result<Game2048, String> {
+        Game2048::from_position(self.config(), &self.grid, self.spawns.clone())
+    }
+
+    pub fn is_solved(&self, game: &Game2048) -> bool {
+        let board = game.board();
+        match self.goal {
+            PuzzleGoal::ReachTile { value } => bitboard::exponent_to_value(board.max_exponent()) >= value,
+            PuzzleGoal::ClearTo { tiles } => {
+                let occupied = board.rows() * board.cols() - board.empty_cells().len();
+                occupied as u32 <= tiles
+            }
+        }
+    }
+
+    // 深度优先搜索一条在步数限制内达成目标的走法。生成序列是固定的，所以这是确定性的搜索；
+    // 子节点按 AI 的局面评估排序，先试最有希望的方向
+    // 返回 Ok(Some(走法))、Ok(None) 表示无解，展开的局面超过 max_nodes 时返回 Err
+    pub fn solve(&self, max_nodes: usize) -> Result<Option<Vec<u8>>, String> {
+        let mut game = self.new_game()?;
+        game.set_history_limit(0);
+
+        let mut search = Search {
+            definition: self,
+            evaluator: WeightedEvaluator::new()
+                .with(2.7, EmptyCells)
+                .with(1.0, MergePotential)
+                .with(1.0, Monotonicity),
+            visited: HashSet::new(),
+            nodes: 0,
+            max_nodes,
+            path: Vec::new(),
+        };
+        if search.run(&game, 0)? {
+            Ok(Some(search.path))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // 发布前的完整校验：格式合法且在搜索预算内找得到解
+    pub fn validate(&self, max_nodes: usize) -> Result<Vec<u8>, String> {
+        self.check()?;
+        match self.solve(max_nodes) {
+            Ok(Some(solution)) => Ok(solution),
+            Ok(None) => Err(format!("Puzzle {} cannot be solved within {} moves", self.id, self.move_limit)),
+            Err(e) => Err(format!("Puzzle {} could not be verified: {}", self.id, e)),
+        }
+    }
+}
+
+struct Search<'a> {
+    definition: &'a PuzzleDefinition,
+    evaluator: WeightedEvaluator,
+    visited: HashSet<(Vec<Vec<u32>>, u32)>,
+    nodes: usize,
+    max_nodes: usize,
+    path: Vec<u8>,
+}
+
+impl Search<'_> {
+    fn run(&mut self, game: &Game2048, depth: u32) -> Result<bool, String> {
+        if self.definition.is_solved(game) {
+            return Ok(true);
+        }
+        if depth == self.definition.move_limit {
+            return Ok(false);
+        }
+        // 生成序列的进度等于已走的步数，所以 (棋盘, 步数) 就能确定局面
+        if !self.visited.insert((game.board().to_grid(), depth)) {
+            return Ok(false);
+        }
+        self.nodes += 1;
+        if self.nodes > self.max_nodes {
+            return Err(format!("Search exceeded {} positions", self.max_nodes));
+        }
+
+        let mut children: Vec<(u8, Game2048, f32)> = (0..4)
+            .filter_map(|direction| {
+                let mut next = game.clone();
+                if !next.play(direction).moved {
+                    return None;
+                }
+                let state = BoardState { grid: next.board().to_grid(), score: next.score() };
+                let value = self.evaluator.evaluate(&state) + next.board().max_exponent() as f32;
+                Some((direction, next, value))
+            })
+            .collect();
+        children.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+
+        for (direction, next, _) in children {
+            self.path.push(direction);
+            if self.run(&next, depth + 1)? {
+                return Ok(true);
+            }
+            self.path.pop();
+        }
+        Ok(false)
+    }
+}
+
+// 谜题模式：在定义好的开局上走棋，达成目标为成功，步数用完或无路可走为失败
+#[wasm_bindgen]
+pub struct Puzzle {
+    definition: PuzzleDefinition,
+    game: Game2048,
+    moves_used: u32,
+}
+
+#[wasm_bindgen]
+impl Puzzle {
+    pub fn load(json: &str) -> Result<Puzzle, JsValue> {
+        PuzzleDefinition::from_json(json)
+            .and_then(Self::new)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    // 内容工具使用：校验谜题并返回一条解法（方向编号数组）
+    pub fn validate_json(json: &str) -> Result<Vec<u8>, JsValue> {
+        PuzzleDefinition::from_json(json)
+            .and_then(|definition| definition.validate(DEFAULT_SEARCH_BUDGET))
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    pub fn move_tiles(&mut self, direction: u8) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.play(direction)).unwrap()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        if self.game.undo() {
+            self.moves_used -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn restart(&mut self) {
+        if let Ok(game) = self.definition.new_game() {
+            self.game = game;
+            self.moves_used = 0;
+        }
+    }
+
+    pub fn status(&self) -> PuzzleStatus {
+        if self.definition.is_solved(&self.game) {
+            PuzzleStatus::Solved
+        } else if self.moves_used >= self.definition.move_limit || !self.game.board().has_legal_move() {
+            PuzzleStatus::Failed
+        } else {
+            PuzzleStatus::InProgress
+        }
+    }
+
+    pub fn moves_left(&self) -> u32 {
+        self.definition.move_limit - self.moves_used
+    }
+
+    pub fn get_state(&self) -> JsValue {
+        self.game.get_state()
+    }
+
+    pub fn get_replay(&self) -> JsValue {
+        self.game.get_replay()
+    }
+}
+
+impl Puzzle {
+    pub fn new(definition: PuzzleDefinition) -> Result<Self, String> {
+        let game = definition.new_game()?;
+        Ok(Self { definition, game, moves_used: 0 })
+    }
+
+    pub fn play(&mut self, direction: u8) -> MoveResult {
+        if self.status() != PuzzleStatus::InProgress {
+            return MoveResult::rejected(self.game.status());
+        }
+        let result = self.game.play(direction);
+        if result.moved {
+            self.moves_used += 1;
+        }
+        result
+    }
+
+    pub fn definition(&self) -> &PuzzleDefinition {
+        &self.definition
+    }
+
+    pub fn game(&self) -> &Game2048 {
+        &self.game
+    }
+}
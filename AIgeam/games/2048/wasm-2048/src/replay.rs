@@ -0,0 +1,67 @@
+use serde::{Serialize, Deserialize};
+
+use crate::config::GameConfig;
+use crate::power_ups::PowerUp;
+
+// 一局游戏的完整录像：种子 + 开局方块 + 玩家的每一个操作
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    #[serde(default)]
+    pub config: GameConfig,
+    pub initial_tiles: Vec<SpawnedTile>,
+    pub events: Vec<ReplayEvent>,
+    // 指定开局（例如谜题）：初始棋盘和按顺序生成的方块，存在时不再使用种子生成方块
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_grid: Option<Vec<Vec<u32>>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spawn_script: Vec<SpawnedTile>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ReplayEvent {
+    Move {
+        direction: u8,
+        spawned: Option<SpawnedTile>,
+    },
+    Undo,
+    Redo,
+    // 使用背包中的道具，重放时按道具类型在背包中查找
+    PowerUp {
+        power_up: PowerUp,
+    },
+    // 对战模式中对手送来的阻碍方块
+    Blocker {
+        tile: SpawnedTile,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct SpawnedTile {
+    pub row: u8,
+    pub col: u8,
+    pub value: u32,
+}
+
+impl Replay {
+    pub fn new(seed: u64, config: GameConfig) -> Self {
+        Self {
+            seed,
+            config,
+            initial_tiles: Vec::new(),
+            events: Vec::new(),
+            initial_grid: None,
+            spawn_script: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, event: ReplayEvent) {
+        self.events.push(event);
+    }
+
+    pub fn move_count(&self) -> usize {
+        self.events.iter()
+            .filter(|event| matches!(event, ReplayEvent::Move { .. }))
+            .count()
+    }
+}
@@ -0,0 +1,116 @@
+use serde::{Serialize, Deserialize};
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+use crate::GameStats;
+
+// 滚动统计只看最近的这么多次输入
+pub const TELEMETRY_WINDOW: usize = 30;
+// 两次输入间隔超过这个毫秒数视为犹豫
+pub const HESITATION_MS: f64 = 1500.0;
+// 窗口内有效移动少于这个数时还看不出玩家的习惯，不判断是否打破习惯
+const PATTERN_MIN_MOVES: u32 = 8;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+struct Input {
+    direction: u8,
+    moved: bool,
+    // 与上一次带时间戳的输入之间的间隔，没有时间戳时为 None
+    gap_ms: Option<f64>,
+    broke_pattern: bool,
+}
+
+// 玩家输入的统计：每次输入（包括没有改变棋盘的无效方向）都记录一次，
+// 时间戳由调用方传入，引擎自身不读取系统时间
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Telemetry {
+    inputs: VecDeque<Input>,
+    last_time_ms: Option<f64>,
+}
+
+impl Telemetry {
+    // 非有限值或早于上一次的时间戳视为没有时间戳，不参与间隔统计
+    pub fn record(&mut self, direction: u8, moved: bool, time_ms: Option<f64>) {
+        let time_ms = time_ms
+            .filter(|now| now.is_finite() && self.last_time_ms.is_none_or(|last| *now >= last));
+        let gap_ms = match (time_ms, self.last_time_ms) {
+            (Some(now), Some(last)) => Some(now - last),
+            _ => None,
+        };
+        let broke_pattern = moved && self.breaks_pattern(direction);
+
+        self.inputs.push_back(Input { direction, moved, gap_ms, broke_pattern });
+        if self.inputs.len() > TELEMETRY_WINDOW {
+            self.inputs.pop_front();
+        }
+        if time_ms.is_some() {
+            self.last_time_ms = time_ms;
+        }
+    }
+
+    // 窗口内每秒的有效移动次数
+    pub fn moves_per_second(&self) -> f32 {
+        let timed: Vec<&Input> = self.inputs.iter().filter(|input| input.gap_ms.is_some()).collect();
+        let elapsed: f64 = timed.iter().filter_map(|input| input.gap_ms).sum();
+        if elapsed <= 0.0 {
+            return 0.0;
+        }
+        let moves = timed.iter().filter(|input| input.moved).count();
+        (moves as f64 * 1000.0 / elapsed) as f32
+    }
+
+    // 没有改变棋盘的输入所占的比例
+    pub fn error_rate(&self) -> f32 {
+        ratio(self.inputs.iter().filter(|input| !input.moved).count(), self.inputs.len())
+    }
+
+    pub fn hesitation_rate(&self) -> f32 {
+        let gaps: Vec<f64> = self.inputs.iter().filter_map(|input| input.gap_ms).collect();
+        ratio(gaps.iter().filter(|&&gap| gap > HESITATION_MS).count(), gaps.len())
+    }
+
+    pub fn think_time_ms(&self) -> f32 {
+        let gaps: Vec<f64> = self.inputs.iter().filter_map(|input| input.gap_ms).collect();
+        if gaps.is_empty() {
+            0.0
+        } else {
+            (gaps.iter().sum::<f64>() / gaps.len() as f64) as f32
+        }
+    }
+
+    // 有效移动中打破玩家习惯方向的比例
+    pub fn pattern_break_rate(&self) -> f32 {
+        let moves = self.inputs.iter().filter(|input| input.moved).count();
+        ratio(self.inputs.iter().filter(|input| input.broke_pattern).count(), moves)
+    }
+
+    pub fn write_stats(&self, stats: &mut GameStats) {
+        stats.move_speed = self.moves_per_second();
+        stats.error_rate = self.error_rate();
+        stats.hesitation_rate = self.hesitation_rate();
+        stats.think_time_ms = self.think_time_ms();
+        stats.pattern_break_rate = self.pattern_break_rate();
+    }
+
+    // 玩家的习惯是窗口内最常用的两个方向（例如守角时的上和左），往其余方向移动视为打破习惯
+    fn breaks_pattern(&self, direction: u8) -> bool {
+        let mut counts = [0u32; 4];
+        for input in self.inputs.iter().filter(|input| input.moved && input.direction < 4) {
+            counts[input.direction as usize] += 1;
+        }
+        if counts.iter().sum::<u32>() < PATTERN_MIN_MOVES {
+            return false;
+        }
+        let mut directions = [0u8, 1, 2, 3];
+        directions.sort_by_key(|&d| Reverse(counts[d as usize]));
+        !directions[..2].contains(&direction)
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f32 / total as f32
+    }
+}
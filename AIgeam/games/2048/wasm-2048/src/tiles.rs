@@ -0,0 +1,93 @@
+use serde::{Serialize, Deserialize};
+
+use crate::board::TileStep;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub row: u8,
+    pub col: u8,
+}
+
+impl From<(usize, usize)> for Cell {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self { row: row as u8, col: col as u8 }
+    }
+}
+
+// 单个方块在一次移动中的变化，前端按 id 对应 DOM 元素做滑动/合并/出现动画
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum TileTransition {
+    Slide { id: u32, from: Cell, to: Cell },
+    Merge { first: u32, second: u32, into: u32, at: Cell, value: u32 },
+    Spawn { id: u32, at: Cell, value: u32 },
+}
+
+// 为棋盘上的每个方块分配稳定 id（0 表示空格），合并后的新方块获得新 id
+#[derive(Clone, Debug)]
+pub struct TileTracker {
+    cols: usize,
+    ids: Vec<u32>,
+    next_id: u32,
+}
+
+impl TileTracker {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            cols,
+            ids: vec![0; rows * cols],
+            next_id: 1,
+        }
+    }
+
+    pub fn id_at(&self, row: usize, col: usize) -> u32 {
+        self.ids[row * self.cols + col]
+    }
+
+    fn allocate(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    pub fn spawn(&mut self, row: usize, col: usize, value: u32) -> TileTransition {
+        let id = self.allocate();
+        self.ids[row * self.cols + col] = id;
+        TileTransition::Spawn { id, at: (row, col).into(), value }
+    }
+
+    // 按移动前棋盘上追踪到的步骤更新 id，并生成对应的动画事件
+    pub fn apply_steps(&mut self, steps: &[TileStep], value_of: impl Fn(u32) -> u32) -> Vec<TileTransition> {
+        let mut ids = vec![0; self.ids.len()];
+        let mut transitions = Vec::with_capacity(steps.len());
+
+        for step in steps {
+            match *step {
+                TileStep::Slide { from, to } => {
+                    let id = self.id_at(from.0, from.1);
+                    ids[to.0 * self.cols + to.1] = id;
+                    if from != to {
+                        transitions.push(TileTransition::Slide { id, from: from.into(), to: to.into() });
+                    }
+                }
+                TileStep::Merge { first, second, to, exponent } => {
+                    let into = self.allocate();
+                    ids[to.0 * self.cols + to.1] = into;
+                    transitions.push(TileTransition::Merge {
+                        first: self.id_at(first.0, first.1),
+                        second: self.id_at(second.0, second.1),
+                        into,
+                        at: to.into(),
+                        value: value_of(exponent),
+                    });
+                }
+            }
+        }
+
+        self.ids = ids;
+        transitions
+    }
+
+    pub fn to_grid(&self) -> Vec<Vec<u32>> {
+        self.ids.chunks(self.cols).map(|row| row.to_vec()).collect()
+    }
+}
@@ -0,0 +1,212 @@
+use serde::{Serialize, Deserialize};
+use wasm_bindgen::prelude::*;
+
+use crate::combo::ComboConfig;
+use crate::config::GameConfig;
+use crate::outcome::{GameStatus, MoveResult};
+use crate::power_ups::PowerUpConfig;
+use crate::tiles::TileTransition;
+use crate::Game2048;
+
+// 限时模式的参数：总时长、大合并奖励的时间、结算时每秒奖励时间折算的分数
+// bonus_times 为 (方块数值, 奖励毫秒)，合并出的方块不小于该数值即可获得奖励，取满足条件的最大一档
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TimeAttackConfig {
+    pub duration_ms: u32,
+    pub bonus_times: Vec<(u32, u32)>,
+    pub points_per_bonus_second: u32,
+}
+
+impl Default for TimeAttackConfig {
+    fn default() -> Self {
+        Self {
+            duration_ms: 120_000,
+            bonus_times: vec![(128, 1_000), (256, 2_000), (512, 4_000), (1024, 8_000), (2048, 15_000)],
+            points_per_bonus_second: 50,
+        }
+    }
+}
+
+impl TimeAttackConfig {
+    fn bonus_for(&self, value: u32) -> u32 {
+        self.bonus_times.iter()
+            .filter(|&&(tile, _)| value >= tile)
+            .max_by_key(|&&(tile, _)| tile)
+            .map_or(0, |&(_, bonus)| bonus)
+    }
+}
+
+// 限时模式的结算结果，mode_score 为局内得分加上奖励时间折算的分数
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TimeAttackResult {
+    pub score: u32,
+    pub max_tile: u32,
+    pub moves: u32,
+    pub bonus_ms: u32,
+    pub elapsed_ms: f64,
+    pub mode_score: u32,
+}
+
+// 限时模式：倒计时归零或无路可走时结束，即 2048-game/src/optimized.rs 中设想的 GameMode::TimeAttack，
+// 剩余时间（那里的 EnhancedGameState::time_remaining）由这里维护
+// 时间全部由调用方传入（毫秒，例如 performance.now()），引擎自身不读取系统时间，
+// 测试时直接给出任意时间点即可；NaN 等无效的时间点会被忽略
+#[wasm_bindgen]
+pub struct TimeAttack {
+    game: Game2048,
+    config: TimeAttackConfig,
+    started_at: f64,
+    last_tick: f64,
+    remaining_ms: f64,
+    bonus_ms: u32,
+    moves: u32,
+}
+
+#[wasm_bindgen]
+impl TimeAttack {
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u64, now_ms: f64) -> Self {
+        Self::start(Game2048::with_seed(seed), TimeAttackConfig::default(), now_ms)
+    }
+
+    pub fn with_config(config: JsValue, seed: u64, now_ms: f64) -> Result<TimeAttack, JsValue> {
+        let config: TimeAttackConfig = serde_wasm_bindgen::from_value(config)?;
+        Ok(Self::start(Game2048::with_seed(seed), config, now_ms))
+    }
+
+    // 道具版限时模式：按默认规则发放道具（包括冻结时间）并使用街机连击计分
+    pub fn with_power_ups(seed: u64, now_ms: f64) -> Self {
+        let config = GameConfig {
+            combo: ComboConfig::arcade(),
+            power_ups: Some(PowerUpConfig::default()),
+            ..GameConfig::default()
+        };
+        Self::start(Game2048::create(config, seed), TimeAttackConfig::default(), now_ms)
+    }
+
+    // 推进倒计时，返回游戏是否仍在进行
+    pub fn tick(&mut self, now_ms: f64) -> bool {
+        self.advance(now_ms);
+        !self.is_over()
+    }
+
+    pub fn move_tiles(&mut self, direction: u8, now_ms: f64) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.play(direction, now_ms)).unwrap()
+    }
+
+    pub fn time_remaining_ms(&self) -> f64 {
+        self.remaining_ms
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.remaining_ms <= 0.0 || self.game.status() == GameStatus::Lost
+    }
+
+    pub fn status(&self) -> GameStatus {
+        if self.remaining_ms <= 0.0 {
+            GameStatus::TimeUp
+        } else {
+            self.game.status()
+        }
+    }
+
+    pub fn get_state(&self) -> JsValue {
+        self.game.get_state()
+    }
+
+    pub fn get_power_ups(&self) -> JsValue {
+        self.game.get_power_ups()
+    }
+
+    pub fn activate_power_up(&mut self, slot: usize, now_ms: f64) -> Result<JsValue, JsValue> {
+        self.use_power_up(slot, now_ms)
+            .map(|transitions| serde_wasm_bindgen::to_value(&transitions).unwrap())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    pub fn get_result(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.result()).unwrap()
+    }
+}
+
+impl TimeAttack {
+    pub fn start(game: Game2048, config: TimeAttackConfig, now_ms: f64) -> Self {
+        Self {
+            game,
+            remaining_ms: config.duration_ms as f64,
+            config,
+            started_at: now_ms,
+            last_tick: now_ms,
+            bonus_ms: 0,
+            moves: 0,
+        }
+    }
+
+    // 先结算到 now_ms 为止流逝的时间，超时则拒绝这一步；每个达到门槛的合并各自奖励时间
+    pub fn play(&mut self, direction: u8, now_ms: f64) -> MoveResult {
+        self.advance(now_ms);
+        if self.is_over() {
+            return MoveResult::rejected(self.status());
+        }
+
+        let result = if now_ms.is_finite() {
+            self.game.play_at(direction, now_ms)
+        } else {
+            self.game.play(direction)
+        };
+        if result.moved {
+            self.moves += 1;
+            let bonus: u32 = result.merges.iter().map(|merge| self.config.bonus_for(merge.value)).sum();
+            self.bonus_ms += bonus;
+            self.remaining_ms += bonus as f64;
+        }
+        result
+    }
+
+    // 先结算到 now_ms 为止流逝的时间，超时后不能再用道具；冻结时间从使用的这一刻开始抵扣
+    pub fn use_power_up(&mut self, slot: usize, now_ms: f64) -> Result<Vec<TileTransition>, String> {
+        self.advance(now_ms);
+        if self.remaining_ms <= 0.0 {
+            return Err("Time is up".to_string());
+        }
+        self.game.use_power_up(slot)
+    }
+
+    pub fn result(&self) -> TimeAttackResult {
+        let score = self.game.score();
+        TimeAttackResult {
+            score,
+            max_tile: crate::bitboard::exponent_to_value(self.game.board().max_exponent()),
+            moves: self.moves,
+            bonus_ms: self.bonus_ms,
+            elapsed_ms: self.last_tick - self.started_at,
+            mode_score: score + self.bonus_ms / 1000 * self.config.points_per_bonus_second,
+        }
+    }
+
+    pub fn game(&self) -> &Game2048 {
+        &self.game
+    }
+
+    // 时间只会向前走，传入更早的时间点不会把倒计时加回去；游戏结束后不再计时
+    fn advance(&mut self, now_ms: f64) {
+        if self.is_over() || !now_ms.is_finite() {
+            return;
+        }
+        // 开局时间无效时，从第一个有效的时间点开始计时
+        if !self.last_tick.is_finite() {
+            self.started_at = now_ms;
+            self.last_tick = now_ms;
+            return;
+        }
+        if now_ms <= self.last_tick {
+            return;
+        }
+        // 冻结时间道具先抵扣；超时的那一刻就是游戏结束的时间，之后流逝的时间不计入用时
+        let wall = now_ms - self.last_tick;
+        let counted = self.game.consume_freeze(wall);
+        let elapsed = counted.min(self.remaining_ms);
+        self.remaining_ms -= elapsed;
+        self.last_tick += wall - counted + elapsed;
+    }
+}
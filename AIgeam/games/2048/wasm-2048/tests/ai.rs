@@ -0,0 +1,53 @@
+// AI 对手的测试：开局就按校准强度削弱，校准状态要和 AI 一起保存，旧存档也要能读
+use game_2048::ai_player::{AILevel, AIPlayer, PlayerGame, SkillCalibrator};
+use wasm_2048::{AIOpponent, Game2048};
+
+#[test]
+fn save_keeps_the_calibrated_strength() {
+    let mut opponent = AIOpponent::new(2).unwrap();
+    let player = Game2048::with_seed(1);
+    for _ in 0..3 {
+        opponent.record_race(&player, 100_000);
+    }
+    assert!(opponent.strength() < 0.5);
+
+    let bytes = opponent.save();
+    let loaded = AIOpponent::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded.strength(), opponent.strength());
+    assert_eq!(loaded.save(), bytes);
+    assert!(AIOpponent::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn player_only_saves_still_load() {
+    let bytes = AIPlayer::new(AILevel::Adaptive).save_state();
+    let loaded = AIOpponent::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded.strength(), 0.5);
+}
+
+#[test]
+fn new_opponent_starts_handicapped() {
+    let bytes = AIOpponent::new(2).unwrap().save();
+    // 魔数和 4 字节长度之后是 AI 本身的存档
+    let len = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+    let player = &bytes[8..8 + len];
+
+    let mut expected = AIPlayer::new(AILevel::Hard);
+    SkillCalibrator::new(0.5).apply(&mut expected);
+    assert_eq!(player, expected.save_state().as_slice());
+    assert_ne!(player, AIPlayer::new(AILevel::Hard).save_state().as_slice());
+}
+
+#[test]
+fn history_sets_the_first_target() {
+    let strong = PlayerGame { score: 100_000, max_tile: 8192, error_rate: 0.1, move_speed: 2.0 };
+    let mut seasoned = AIOpponent::with_history(AIPlayer::new(AILevel::Hard), vec![strong; 5]);
+    let mut fresh = AIOpponent::new(2).unwrap();
+
+    // 同样一局开局就结束的对战：有历史的对手按高水平目标变强，没有历史的只看这一局而变弱
+    let player = Game2048::with_seed(1);
+    seasoned.record_race(&player, 2000);
+    fresh.record_race(&player, 2000);
+    assert!(seasoned.strength() > 0.5);
+    assert!(fresh.strength() < 0.5);
+}
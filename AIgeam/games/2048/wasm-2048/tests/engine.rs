@@ -0,0 +1,226 @@
+// 引擎的公共测试：标量构建和 simd 构建都要通过同一组测试，
+// 分别用 `cargo test` 和 `cargo +nightly test --features simd` 运行
+use game_2048_core::{spawn_exponent, CLASSIC_SPAWN_WEIGHTS};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use wasm_2048::{ComboConfig, Game2048, GameConfig, SpawnedTile};
+
+// 默认配置为经典计分，得分就是合并出的方块之和
+fn position(grid: &[[u32; 4]; 4]) -> Game2048 {
+    let grid: Vec<Vec<u32>> = grid.iter().map(|row| row.to_vec()).collect();
+    Game2048::from_position(GameConfig::default(), &grid, Vec::new()).unwrap()
+}
+
+#[test]
+fn moves_compress_and_merge_once_per_tile() {
+    let mut game = position(&[[2, 2, 4, 4], [2, 2, 2, 0], [0, 4, 0, 4], [8, 0, 8, 8]]);
+    let result = game.play(3);
+    assert!(result.moved);
+    assert_eq!(result.merges.len(), 5);
+    assert_eq!(
+        game.board().to_grid(),
+        vec![vec![4, 8, 0, 0], vec![4, 2, 0, 0], vec![8, 0, 0, 0], vec![16, 8, 0, 0]]
+    );
+    assert_eq!(game.score(), 4 + 8 + 4 + 8 + 16);
+}
+
+#[test]
+fn vertical_moves_match_horizontal_moves_on_the_transposed_board() {
+    let mut vertical = position(&[[2, 0, 4, 8], [2, 4, 4, 0], [4, 4, 0, 8], [4, 0, 2, 2]]);
+    let mut horizontal = position(&[[2, 2, 4, 4], [0, 4, 4, 0], [4, 4, 0, 2], [8, 0, 8, 2]]);
+    vertical.play(0);
+    horizontal.play(3);
+
+    let grid = vertical.board().to_grid();
+    let transposed: Vec<Vec<u32>> = (0..4).map(|j| (0..4).map(|i| grid[i][j]).collect()).collect();
+    assert_eq!(transposed, horizontal.board().to_grid());
+    assert_eq!(vertical.score(), horizontal.score());
+}
+
+#[test]
+fn largest_tile_does_not_merge_on_a_packed_board() {
+    let mut game = position(&[[32768, 32768, 0, 0], [2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4]]);
+    let result = game.play(3);
+    assert!(!result.moved);
+    assert_eq!(game.score(), 0);
+}
+
+#[test]
+fn combo_multipliers_are_opt_in() {
+    let grid = vec![vec![2, 2, 4, 4], vec![2, 2, 2, 0], vec![0; 4], vec![0; 4]];
+    let mut plain = Game2048::from_position(GameConfig::default(), &grid, Vec::new()).unwrap();
+    let arcade = GameConfig { combo: ComboConfig::arcade(), ..GameConfig::default() };
+    let mut combos = Game2048::from_position(arcade, &grid, Vec::new()).unwrap();
+
+    // 一步三次合并，连击数为 3，街机计分按 x1.1 结算
+    let result = plain.play(3);
+    assert_eq!(result.combo, 3);
+    assert_eq!(plain.score(), 4 + 8 + 4);
+    combos.play(3);
+    assert_eq!(combos.score(), (4 + 8 + 4) * 110 / 100);
+}
+
+#[test]
+fn combo_count_saturates_and_bonus_is_bounded() {
+    let combo = ComboConfig { multi_merge_bonus: 10, ..ComboConfig::default() };
+    assert!(combo.validate().is_ok());
+    assert_eq!(combo.extend(u32::MAX - 1, 8), u32::MAX);
+    assert_eq!(combo.extend(u32::MAX, 1), u32::MAX);
+    assert_eq!(combo.extend(3, 2), 3 + 1 + 10);
+
+    let huge = ComboConfig { multi_merge_bonus: u32::MAX, ..ComboConfig::default() };
+    assert!(huge.validate().is_err());
+    assert!(Game2048::from_config(GameConfig { combo: huge, ..GameConfig::default() }, 1).is_err());
+}
+
+#[test]
+fn seeded_game_is_reproducible_and_replays() {
+    let play = || {
+        let mut game = Game2048::with_seed(2048);
+        for i in 0..300u32 {
+            game.play([0, 3, 0, 3, 1, 2][i as usize % 6]);
+        }
+        game
+    };
+    let game = play();
+    assert_eq!(game.board(), play().board());
+    assert_eq!(game.score(), play().score());
+
+    let replayed = Game2048::replay(game.replay_log()).unwrap();
+    assert_eq!(replayed.board(), game.board());
+    assert_eq!(replayed.score(), game.score());
+}
+
+#[test]
+fn seeded_game_matches_recorded_result() {
+    let mut game = Game2048::with_seed(42);
+    for i in 0..200u32 {
+        game.play((i % 4) as u8);
+    }
+    assert_eq!(game.score(), GOLDEN_SCORE);
+    assert_eq!(game.board().to_grid(), GOLDEN_GRID.iter().map(|row| row.to_vec()).collect::<Vec<_>>());
+}
+
+#[test]
+fn shared_spawns_follow_one_sequence_on_different_boards() {
+    let config = GameConfig { shared_spawns: true, ..GameConfig::default() };
+    let mut games = [Game2048::from_config(config.clone(), 9).unwrap(), Game2048::from_config(config, 9).unwrap()];
+    games[1].place_blocker(empty_cell(&games[1].board().to_grid())).unwrap();
+
+    // 每次生成先在 16 个格子中抽目标，再抽数值，与棋盘无关
+    let mut rng = ChaCha8Rng::seed_from_u64(9);
+    let expected: Vec<(usize, u32)> = (0..200)
+        .map(|_| (rng.gen_range(0..16), 1 << spawn_exponent(&CLASSIC_SPAWN_WEIGHTS, &mut rng)))
+        .collect();
+
+    for game in &mut games {
+        let mut grid = vec![vec![0; 4]; 4];
+        let mut index = 0;
+        for tile in game.replay_log().initial_tiles.clone() {
+            assert_spawned_from(&mut grid, tile, expected[index]);
+            index += 1;
+        }
+        for i in 0..120 {
+            let result = game.play([0, 3, 0, 3, 1, 2][i % 6]);
+            if let Some(tile) = result.spawned {
+                let mut grid = game.board().to_grid();
+                grid[tile.row as usize][tile.col as usize] = 0;
+                assert_spawned_from(&mut grid, tile, expected[index]);
+                index += 1;
+            }
+        }
+    }
+    assert_ne!(games[0].board(), games[1].board());
+}
+
+fn empty_cell(grid: &[Vec<u32>]) -> SpawnedTile {
+    let index = grid.iter().flatten().position(|&value| value == 0).unwrap();
+    SpawnedTile { row: (index / 4) as u8, col: (index % 4) as u8, value: 2 }
+}
+
+// 新方块应落在目标格子或其后第一个空格上
+fn assert_spawned_from(grid: &mut [Vec<u32>], tile: SpawnedTile, (target, value): (usize, u32)) {
+    let cell = (0..16).map(|k| (target + k) % 16).find(|&k| grid[k / 4][k % 4] == 0).unwrap();
+    assert_eq!((tile.row as usize * 4 + tile.col as usize, tile.value), (cell, value));
+    grid[cell / 4][cell % 4] = value;
+}
+
+// 当前局面下第一个能移动的方向
+fn first_legal_move(game: &Game2048) -> u8 {
+    (0..4).find(|&direction| game.clone().play(direction).moved).unwrap()
+}
+
+#[test]
+fn undo_restores_board_score_and_spawns() {
+    let mut game = Game2048::with_seed(5);
+    for i in 0..20u32 {
+        game.play((i % 4) as u8);
+    }
+    let (board, score) = (game.board().clone(), game.score());
+    let direction = first_legal_move(&game);
+    let played = game.play(direction);
+    let after = game.board().clone();
+
+    assert!(game.undo());
+    assert_eq!((game.board(), game.score()), (&board, score));
+    // 随机数状态也一起回退，重走同一步生成同样的方块
+    assert_eq!(game.play(direction).spawned, played.spawned);
+    assert_eq!(game.board(), &after);
+}
+
+#[test]
+fn redo_is_dropped_after_a_new_move() {
+    let mut game = Game2048::with_seed(6);
+    let direction = first_legal_move(&game);
+    game.play(direction);
+    let after = (game.board().clone(), game.score());
+
+    assert!(game.undo());
+    assert!(game.can_redo());
+    assert!(game.redo());
+    assert_eq!((game.board().clone(), game.score()), after);
+
+    assert!(game.undo());
+    let other = first_legal_move(&game);
+    game.play(other);
+    assert!(!game.can_redo());
+    assert!(!game.redo());
+}
+
+#[test]
+fn undo_history_respects_its_limit() {
+    let mut game = Game2048::with_seed(7);
+    game.set_history_limit(3);
+    for _ in 0..5 {
+        let direction = first_legal_move(&game);
+        game.play(direction);
+    }
+    for _ in 0..3 {
+        assert!(game.undo());
+    }
+    assert!(!game.can_undo());
+    assert!(!game.undo());
+}
+
+#[test]
+fn larger_boards_use_the_same_rules() {
+    let config = GameConfig { rows: 5, cols: 5, ..GameConfig::default() };
+    let grid = vec![
+        vec![2, 2, 2, 0, 2],
+        vec![0; 5],
+        vec![0; 5],
+        vec![0; 5],
+        vec![4, 0, 4, 4, 4],
+    ];
+    let spawns = vec![SpawnedTile { row: 2, col: 2, value: 2 }];
+    let mut game = Game2048::from_position(config, &grid, spawns).unwrap();
+    game.play(3);
+    assert_eq!(game.board().to_grid()[0], vec![4, 4, 0, 0, 0]);
+    assert_eq!(game.board().to_grid()[4], vec![8, 8, 0, 0, 0]);
+    assert_eq!(game.score(), 4 + 4 + 8 + 8);
+}
+
+// 种子 42、按上右下左循环走 200 步的结果，由标量构建（`cargo test -p wasm_2048`）记录，
+// simd 构建（`cargo +nightly test -p wasm_2048 --features simd`）必须得到完全相同的结果
+const GOLDEN_SCORE: u32 = 2448;
+const GOLDEN_GRID: [[u32; 4]; 4] = [[4, 2, 0, 2], [2, 64, 16, 2], [8, 2, 4, 256], [2, 4, 64, 4]];
@@ -0,0 +1,42 @@
+// 道具的测试：撤销道具不能用来重复领取连击奖励
+use wasm_2048::{Game2048, GameConfig, PowerUp, PowerUpConfig};
+
+fn power_up_game() -> Game2048 {
+    let config = GameConfig {
+        power_ups: Some(PowerUpConfig {
+            combo_rewards: vec![(2, PowerUp::ShuffleBoard)],
+            tile_rewards: vec![(8, PowerUp::UndoLastMove)],
+            max_inventory: 5,
+        }),
+        ..GameConfig::default()
+    };
+    let grid = vec![vec![4, 4, 2, 2], vec![2, 2, 0, 0], vec![0; 4], vec![0; 4]];
+    Game2048::from_position(config, &grid, Vec::new()).unwrap()
+}
+
+#[test]
+fn undo_does_not_regrant_combo_rewards() {
+    let mut game = power_up_game();
+    // 一步三次合并，连击数 3，同时合成出 8
+    let first = game.play(3);
+    assert_eq!(first.combo, 3);
+    assert_eq!(first.power_ups_granted, vec![PowerUp::ShuffleBoard, PowerUp::UndoLastMove]);
+
+    game.use_power_up(1).unwrap();
+    let again = game.play(3);
+    assert_eq!(again.combo, 3);
+    assert!(again.power_ups_granted.is_empty());
+    assert_eq!(game.power_ups().inventory, vec![PowerUp::ShuffleBoard]);
+}
+
+#[test]
+fn broken_chain_resets_the_rewarded_combo() {
+    let mut game = power_up_game();
+    game.play(3);
+    assert_eq!(game.power_ups().rewarded_combo, 2);
+    // 向右只滑动不合并，连击中断，下一条连击链可以重新领奖
+    let broken = game.play(1);
+    assert!(broken.moved);
+    assert_eq!(broken.combo, 0);
+    assert_eq!(game.power_ups().rewarded_combo, 0);
+}
@@ -0,0 +1,124 @@
+// 谜题模式的测试：求解器判断能否解出，脚本里的方块按文件生成，步数和撤销要计入状态
+use wasm_2048::{Puzzle, PuzzleDefinition, PuzzleStatus};
+
+const BUDGET: usize = 200_000;
+
+// 4x4 棋盘第一行为 4 4 8，向左两次合成出 16
+fn two_step_puzzle(move_limit: u32) -> PuzzleDefinition {
+    PuzzleDefinition::from_json(&format!(
+        r#"{{"id": "two-step", "grid": [[4,4,8,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]],
+            "move_limit": {}, "goal": {{"type": "reach_tile", "value": 16}}}}"#,
+        move_limit
+    ))
+    .unwrap()
+}
+
+#[test]
+fn solvable_puzzle_is_solved() {
+    let definition = two_step_puzzle(2);
+    let solution = definition.validate(BUDGET).unwrap();
+    assert_eq!(solution.len(), 2);
+
+    let mut puzzle = Puzzle::new(definition).unwrap();
+    for direction in solution {
+        assert!(puzzle.play(direction).moved);
+    }
+    assert_eq!(puzzle.status(), PuzzleStatus::Solved);
+}
+
+#[test]
+fn unsolvable_puzzle_is_reported() {
+    let definition = two_step_puzzle(1);
+    assert_eq!(definition.solve(BUDGET), Ok(None));
+    assert!(definition.validate(BUDGET).is_err());
+}
+
+#[test]
+fn search_stops_at_the_node_budget() {
+    // 根节点展开后，第二个局面就超出预算
+    assert!(two_step_puzzle(2).solve(1).is_err());
+}
+
+#[test]
+fn repeated_positions_are_pruned() {
+    // 3x3 棋盘上只有一个 2、没有新方块，永远合成不出 8；
+    // 方块只有四个角可去，去重后 50 步的搜索远用不完 1000 个局面
+    let definition = PuzzleDefinition::from_json(
+        r#"{"id": "lonely", "grid": [[2,0,0],[0,0,0],[0,0,0]],
+            "move_limit": 50, "goal": {"type": "reach_tile", "value": 8}}"#,
+    )
+    .unwrap();
+    assert_eq!(definition.solve(1000), Ok(None));
+}
+
+#[test]
+fn scripted_spawns_land_where_the_file_says() {
+    let definition = PuzzleDefinition::from_json(
+        r#"{"id": "spawns", "grid": [[2,0,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]],
+            "spawns": [{"row": 3, "col": 3, "value": 4}],
+            "move_limit": 5, "goal": {"type": "reach_tile", "value": 64}}"#,
+    )
+    .unwrap();
+    let mut puzzle = Puzzle::new(definition).unwrap();
+    assert!(puzzle.play(1).moved);
+    assert_eq!(
+        puzzle.game().board().to_grid(),
+        vec![vec![0, 0, 0, 2], vec![0; 4], vec![0; 4], vec![0, 0, 0, 4]]
+    );
+
+    // 脚本用完后不再生成新方块
+    assert!(puzzle.play(3).moved);
+    assert_eq!(
+        puzzle.game().board().to_grid(),
+        vec![vec![2, 0, 0, 0], vec![0; 4], vec![0; 4], vec![4, 0, 0, 0]]
+    );
+}
+
+#[test]
+fn malformed_definitions_are_rejected() {
+    let valid = r#"{"id": "p", "grid": [[2,2,0],[0,0,0],[0,0,0]], "move_limit": 3, "goal": {"type": "reach_tile", "value": 8}}"#;
+    assert!(PuzzleDefinition::from_json(valid).is_ok());
+
+    for json in [
+        "{",
+        r#"{"id": "p", "grid": [[2,2,0],[0,0,0],[0,0,0]], "move_limit": 3}"#,
+        r#"{"id": "p", "grid": [[2,2,0],[0,0,0],[0,0,0]], "move_limit": 0, "goal": {"type": "reach_tile", "value": 8}}"#,
+        r#"{"id": "p", "grid": [[2,2,0],[0,0,0],[0,0,0]], "move_limit": 3, "goal": {"type": "clear_to", "tiles": 0}}"#,
+        r#"{"id": "p", "grid": [[2,2,0],[0,0,0],[0,0,0]], "move_limit": 3, "goal": {"type": "score", "value": 8}}"#,
+        r#"{"id": "p", "grid": [[3,2,0],[0,0,0],[0,0,0]], "move_limit": 3, "goal": {"type": "reach_tile", "value": 8}}"#,
+        r#"{"id": "p", "grid": [[2,2],[0,0]], "move_limit": 3, "goal": {"type": "reach_tile", "value": 8}}"#,
+        r#"{"id": "p", "grid": [[2,2,0],[0,0,0],[0,0,0]], "spawns": [{"row": 5, "col": 0, "value": 2}],
+            "move_limit": 3, "goal": {"type": "reach_tile", "value": 8}}"#,
+    ] {
+        assert!(PuzzleDefinition::from_json(json).is_err(), "{}", json);
+    }
+    assert!(PuzzleDefinition::list_from_json(&format!("[{}, {{}}]", valid)).is_err());
+}
+
+#[test]
+fn status_follows_moves_and_undo() {
+    let mut puzzle = Puzzle::new(two_step_puzzle(2)).unwrap();
+    assert!(puzzle.play(3).moved);
+    assert_eq!(puzzle.status(), PuzzleStatus::InProgress);
+    assert_eq!(puzzle.moves_left(), 1);
+
+    // 撤销退回一步，步数也还回来
+    assert!(puzzle.undo());
+    assert_eq!(puzzle.moves_left(), 2);
+    assert_eq!(puzzle.game().board().to_grid()[0], vec![4, 4, 8, 0]);
+
+    // 没有移动的方向不计步数
+    assert!(!puzzle.play(0).moved);
+    assert_eq!(puzzle.moves_left(), 2);
+
+    assert!(puzzle.play(2).moved);
+    assert!(puzzle.play(3).moved);
+    assert_eq!(puzzle.status(), PuzzleStatus::Failed);
+    assert!(!puzzle.play(1).moved);
+
+    puzzle.restart();
+    assert!(puzzle.play(3).moved);
+    assert!(puzzle.play(3).moved);
+    assert_eq!(puzzle.status(), PuzzleStatus::Solved);
+    assert!(!puzzle.play(1).moved);
+}
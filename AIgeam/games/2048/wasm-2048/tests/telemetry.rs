@@ -0,0 +1,68 @@
+// 输入统计的测试：时间戳由调用方传入，直接给出时间点即可
+use wasm_2048::Telemetry;
+
+fn record_all(telemetry: &mut Telemetry, inputs: &[(u8, bool, f64)]) {
+    for &(direction, moved, time_ms) in inputs {
+        telemetry.record(direction, moved, Some(time_ms));
+    }
+}
+
+#[test]
+fn move_speed_counts_moves_per_second() {
+    let mut telemetry = Telemetry::default();
+    record_all(&mut telemetry, &[(0, true, 0.0), (3, true, 250.0), (0, true, 500.0), (3, false, 750.0), (0, true, 1000.0)]);
+    // 第一次输入没有间隔；之后 1 秒内 3 次有效移动
+    assert_eq!(telemetry.moves_per_second(), 3.0);
+    assert_eq!(telemetry.think_time_ms(), 250.0);
+}
+
+#[test]
+fn inputs_that_do_not_move_count_as_errors() {
+    let mut telemetry = Telemetry::default();
+    for moved in [true, false, true, true] {
+        telemetry.record(0, moved, None);
+    }
+    assert_eq!(telemetry.error_rate(), 0.25);
+    // 没有时间戳时不统计速度
+    assert_eq!(telemetry.moves_per_second(), 0.0);
+}
+
+#[test]
+fn hesitation_starts_above_the_threshold() {
+    let mut telemetry = Telemetry::default();
+    record_all(&mut telemetry, &[(0, true, 0.0), (3, true, 1_500.0), (0, true, 3_001.0)]);
+    // 间隔 1500ms 不算犹豫，1501ms 算
+    assert_eq!(telemetry.hesitation_rate(), 0.5);
+}
+
+#[test]
+fn moves_outside_the_two_habitual_directions_break_the_pattern() {
+    let mut telemetry = Telemetry::default();
+    // 习惯还没形成时往哪个方向走都不算
+    for direction in [0, 3, 0, 3, 1] {
+        telemetry.record(direction, true, None);
+    }
+    assert_eq!(telemetry.pattern_break_rate(), 0.0);
+
+    for direction in [0, 3, 0, 3] {
+        telemetry.record(direction, true, None);
+    }
+    // 常用方向为上和左，向上不算，向下算；无效输入不参与
+    telemetry.record(0, true, None);
+    telemetry.record(2, false, None);
+    telemetry.record(2, true, None);
+    assert_eq!(telemetry.pattern_break_rate(), 1.0 / 11.0);
+}
+
+#[test]
+fn invalid_and_backward_timestamps_are_ignored() {
+    let mut telemetry = Telemetry::default();
+    record_all(
+        &mut telemetry,
+        &[(0, true, f64::NAN), (3, true, 0.0), (0, true, f64::INFINITY), (3, true, 1_000.0), (0, true, 500.0), (3, true, 2_000.0)],
+    );
+    // 只有 0 -> 1000 -> 2000 两段间隔
+    assert_eq!(telemetry.think_time_ms(), 1_000.0);
+    assert_eq!(telemetry.moves_per_second(), 1.0);
+    assert_eq!(telemetry.hesitation_rate(), 0.0);
+}
@@ -0,0 +1,68 @@
+// 方块 id 和动画事件的测试：前端按 id 做动画，同一个方块在移动中必须保持同一个 id
+use wasm_2048::{Cell, Game2048, GameConfig, SpawnedTile, TileTransition};
+
+fn cell(row: u8, col: u8) -> Cell {
+    Cell { row, col }
+}
+
+#[test]
+fn merge_move_reports_slides_merges_and_spawns() {
+    let grid = vec![vec![2, 2, 0, 4], vec![0, 0, 8, 0], vec![0; 4], vec![0; 4]];
+    let spawns = vec![SpawnedTile { row: 3, col: 3, value: 2 }];
+    let mut game = Game2048::from_position(GameConfig::default(), &grid, spawns).unwrap();
+    // 开局方块按行优先依次编号
+    assert_eq!(game.tile_ids(), vec![vec![1, 2, 0, 3], vec![0, 0, 4, 0], vec![0; 4], vec![0; 4]]);
+
+    let result = game.play(3);
+    assert_eq!(result.transitions, vec![
+        TileTransition::Merge { first: 1, second: 2, into: 5, at: cell(0, 0), value: 4 },
+        TileTransition::Slide { id: 3, from: cell(0, 3), to: cell(0, 1) },
+        TileTransition::Slide { id: 4, from: cell(1, 2), to: cell(1, 0) },
+        TileTransition::Spawn { id: 6, at: cell(3, 3), value: 2 },
+    ]);
+    assert_eq!(game.tile_ids(), vec![vec![5, 3, 0, 0], vec![4, 0, 0, 0], vec![0; 4], vec![0, 0, 0, 6]]);
+}
+
+// 随机对局中每一步的动画事件都要和前后两个局面的 id 对得上，没有动的方块 id 不变
+#[test]
+fn seeded_game_keeps_tile_ids_consistent() {
+    let mut game = Game2048::with_seed(42);
+    for i in 0..200u32 {
+        let before = game.tile_ids();
+        let result = game.play([0, 3, 1, 3, 2, 3][i as usize % 6]);
+        let after = game.tile_ids();
+        if !result.moved {
+            assert_eq!(after, before);
+            continue;
+        }
+
+        let id_at = |grid: &Vec<Vec<u32>>, at: Cell| grid[at.row as usize][at.col as usize];
+        let mut moved = Vec::new();
+        for transition in &result.transitions {
+            match *transition {
+                TileTransition::Slide { id, from, to } => {
+                    assert_eq!((id_at(&before, from), id_at(&after, to)), (id, id));
+                    moved.push(id);
+                }
+                TileTransition::Merge { first, second, into, at, .. } => {
+                    assert_eq!(id_at(&after, at), into);
+                    assert!(before.iter().flatten().any(|&id| id == first));
+                    assert!(before.iter().flatten().any(|&id| id == second));
+                    assert!(before.iter().flatten().all(|&id| id != into));
+                    moved.extend([first, second]);
+                }
+                TileTransition::Spawn { id, at, .. } => {
+                    assert_eq!(id_at(&after, at), id);
+                    assert!(before.iter().flatten().all(|&old| old != id));
+                }
+            }
+        }
+        for (row_before, row_after) in before.iter().zip(&after) {
+            for (&old, &new) in row_before.iter().zip(row_after) {
+                if old != 0 && !moved.contains(&old) {
+                    assert_eq!(new, old);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+// 限时模式的测试：时间全部由调用方传入，直接给出时间点即可
+use wasm_2048::{Game2048, GameConfig, GameStatus, PowerUp, PowerUpConfig, TimeAttack, TimeAttackConfig};
+
+fn start(now_ms: f64) -> TimeAttack {
+    TimeAttack::start(Game2048::with_seed(1), TimeAttackConfig::default(), now_ms)
+}
+
+#[test]
+fn countdown_expires_and_rejects_moves() {
+    let mut game = start(0.0);
+    assert!(game.tick(119_999.0));
+    assert!(!game.tick(120_000.0));
+    assert_eq!(game.status(), GameStatus::TimeUp);
+
+    let result = game.play(0, 130_000.0);
+    assert!(!result.moved);
+    // 超时之后的时间不计入用时
+    game.tick(200_000.0);
+    assert_eq!(game.result().elapsed_ms, 120_000.0);
+}
+
+#[test]
+fn big_merges_add_bonus_time() {
+    let grid = vec![vec![64, 64, 0, 0], vec![2, 4, 8, 16], vec![0; 4], vec![0; 4]];
+    let board = Game2048::from_position(GameConfig::default(), &grid, Vec::new()).unwrap();
+    let config = TimeAttackConfig::default();
+    let mut game = TimeAttack::start(board, config.clone(), 0.0);
+
+    assert!(game.play(3, 1_000.0).moved);
+    assert_eq!(game.time_remaining_ms(), 120_000.0);
+    let result = game.result();
+    assert_eq!(result.bonus_ms, 1_000);
+    assert_eq!(result.moves, 1);
+    assert_eq!(result.mode_score, result.score + config.points_per_bonus_second);
+}
+
+#[test]
+fn invalid_or_earlier_times_are_ignored() {
+    let mut game = start(0.0);
+    assert!(game.tick(f64::NAN));
+    assert_eq!(game.time_remaining_ms(), 120_000.0);
+
+    game.tick(5_000.0);
+    game.tick(1_000.0);
+    assert_eq!(game.time_remaining_ms(), 115_000.0);
+
+    game.play(0, f64::NAN);
+    game.play(1, f64::INFINITY);
+    assert!(!game.is_over());
+    assert_eq!(game.time_remaining_ms(), 115_000.0);
+}
+
+#[test]
+fn invalid_start_time_starts_at_the_first_valid_tick() {
+    let mut game = start(f64::NAN);
+    assert!(game.tick(1_000.0));
+    assert!(game.tick(3_000.0));
+    assert_eq!(game.time_remaining_ms(), 118_000.0);
+    assert_eq!(game.result().elapsed_ms, 2_000.0);
+}
+
+#[test]
+fn earned_freeze_extends_the_countdown() {
+    // 与 TimeAttack::with_power_ups 相同的道具规则，合成出 512 时获得 10 秒冻结
+    let config = TimeAttack::with_power_ups(1, 0.0).game().config().clone();
+    let grid = vec![vec![256, 256, 0, 0], vec![2, 4, 8, 16], vec![0; 4], vec![0; 4]];
+    let board = Game2048::from_position(config, &grid, Vec::new()).unwrap();
+    let mut game = TimeAttack::start(board, TimeAttackConfig::default(), 0.0);
+
+    let result = game.play(3, 1_000.0);
+    let freeze = PowerUp::FreezeTime { duration: 10_000 };
+    let slot = result.power_ups_granted.iter().position(|&power_up| power_up == freeze).unwrap();
+    // 合成 512 奖励 4 秒
+    assert_eq!(game.time_remaining_ms(), 123_000.0);
+
+    game.use_power_up(slot, 2_000.0).unwrap();
+    assert_eq!(game.time_remaining_ms(), 122_000.0);
+    game.tick(12_000.0);
+    assert_eq!(game.time_remaining_ms(), 122_000.0);
+    game.tick(13_000.0);
+    assert_eq!(game.time_remaining_ms(), 121_000.0);
+
+    // 超时后道具不能再用
+    game.tick(200_000.0);
+    assert!(game.use_power_up(0, 200_000.0).is_err());
+}
+
+#[test]
+fn untimed_power_up_games_do_not_grant_freeze() {
+    let is_freeze = |&(_, power_up): &(u32, PowerUp)| matches!(power_up, PowerUp::FreezeTime { .. });
+    let untimed = PowerUpConfig::untimed();
+    assert!(!untimed.combo_rewards.iter().chain(&untimed.tile_rewards).any(is_freeze));
+    assert_eq!(Game2048::with_power_ups(1).config().power_ups, Some(untimed));
+
+    let timed = PowerUpConfig::default();
+    assert!(timed.combo_rewards.iter().chain(&timed.tile_rewards).any(is_freeze));
+}
@@ -0,0 +1,82 @@
+use std::collections::{BTreeMap, HashMap};
+use serde::{Serialize, Deserialize};
+
+use crate::verification::{GameSessions, GameTicket, ScoreProof, VerificationConfig};
+
+// 排行榜，从 multiplayer.rs 草稿中拆出来单独编译；
+// 玩家 id 由接入层给出（例如 UUID 的字符串形式），时间均为服务端的毫秒时间
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Leaderboard {
+    scores: BTreeMap<u32, Vec<ScoreEntry>>,
+    achievements: HashMap<String, Vec<Achievement>>,
+    #[serde(default)]
+    verification: VerificationConfig,
+    // 已发放、尚未提交成绩的对局，只在内存里保存
+    #[serde(skip)]
+    sessions: GameSessions,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScoreEntry {
+    pub player_id: String,
+    pub player_name: String,
+    pub score: u32,
+    pub timestamp_ms: u64,
+    pub game_mode: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Achievement {
+    FirstGame,
+    Score1000,
+    Score10000,
+    PerfectGame,
+    SpeedRun { time: u32 },
+}
+
+impl Leaderboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_verification(mut self, verification: VerificationConfig) -> Self {
+        self.verification = verification;
+        self
+    }
+
+    // 开局时调用，客户端用凭据里的种子开局，提交成绩时带回 nonce；now_ms 为服务端时间
+    pub fn start_game(&mut self, now_ms: u64) -> GameTicket {
+        self.sessions.expire(now_ms.saturating_sub(self.verification.max_game_ms));
+        self.sessions.issue(now_ms)
+    }
+
+    // 只有服务端发放的对局、重新模拟录像得到相同分数、且操作时间合理的成绩才会进入排行榜
+    pub fn add_score(&mut self, entry: ScoreEntry, proof: &ScoreProof, now_ms: u64) -> Result<(), String> {
+        let ticket = self.sessions.take(proof.nonce)?;
+        self.verification.verify(entry.score, proof, &ticket, now_ms)?;
+        self.scores.entry(entry.score)
+            .or_default()
+            .push(entry);
+        Ok(())
+    }
+
+    pub fn get_top_scores(&self, limit: usize) -> Vec<&ScoreEntry> {
+        self.scores.iter()
+            .rev()
+            .flat_map(|(_, entries)| entries.iter())
+            .take(limit)
+            .collect()
+    }
+
+    pub fn unlock_achievement(&mut self, player_id: &str, achievement: Achievement) {
+        self.achievements.entry(player_id.to_string())
+            .or_default()
+            .push(achievement);
+    }
+
+    pub fn get_achievements(&self, player_id: &str) -> Vec<Achievement> {
+        self.achievements.get(player_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
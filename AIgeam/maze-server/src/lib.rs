@@ -0,0 +1,4 @@
+// enhanced.rs、optimized.rs、multiplayer.rs 仍是草稿，尚未接入编译
+pub mod leaderboard;
+pub mod verification;
+pub mod versus;
@@ -2,10 +2,12 @@ use std::collections::HashMap;
 use tokio::sync::{mpsc, RwLock};
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
-use std::collections::BTreeMap;
 use chrono::Utc;
 use chrono::Duration;
 
+// 排行榜已移到 leaderboard.rs 单独编译
+pub use crate::leaderboard::{Achievement, Leaderboard, ScoreEntry};
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Player {
     pub id: Uuid,
@@ -91,65 +93,6 @@ impl MultiplayerServer {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-pub struct Leaderboard {
-    scores: BTreeMap<u32, Vec<ScoreEntry>>,
-    achievements: HashMap<Uuid, Vec<Achievement>>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct ScoreEntry {
-    pub player_id: Uuid,
-    pub player_name: String,
-    pub score: u32,
-    pub timestamp: chrono::DateTime<Utc>,
-    pub game_mode: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub enum Achievement {
-    FirstGame,
-    Score1000,
-    Score10000,
-    PerfectGame,
-    SpeedRun { time: u32 },
-}
-
-impl Leaderboard {
-    pub fn new() -> Self {
-        Self {
-            scores: BTreeMap::new(),
-            achievements: HashMap::new(),
-        }
-    }
-
-    pub fn add_score(&mut self, entry: ScoreEntry) {
-        self.scores.entry(entry.score)
-            .or_insert_with(Vec::new)
-            .push(entry);
-    }
-
-    pub fn get_top_scores(&self, limit: usize) -> Vec<&ScoreEntry> {
-        self.scores.iter()
-            .rev()
-            .flat_map(|(_, entries)| entries.iter())
-            .take(limit)
-            .collect()
-    }
-
-    pub fn unlock_achievement(&mut self, player_id: Uuid, achievement: Achievement) {
-        self.achievements.entry(player_id)
-            .or_insert_with(Vec::new)
-            .push(achievement);
-    }
-
-    pub fn get_achievements(&self, player_id: Uuid) -> Vec<Achievement> {
-        self.achievements.get(&player_id)
-            .cloned()
-            .unwrap_or_default()
-    }
-}
-
 #[derive(Serialize, Deserialize)]
 pub struct GameAnalytics {
     player_stats: HashMap<Uuid, PlayerStats>,
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Serialize, Deserialize};
+use wasm_2048::{Game2048, GameConfig, Replay, ReplayEvent};
+
+// 成绩校验的阈值：两次操作的最小间隔、整局的平均操作速度上限、允许重新模拟的最大操作数，
+// 一局从发放到提交的最长时间，以及客户端时间戳相对服务端计时允许超出的误差（网络延迟等）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VerificationConfig {
+    pub min_event_interval_ms: u64,
+    pub max_moves_per_second: f64,
+    pub max_events: usize,
+    #[serde(default = "default_max_game_ms")]
+    pub max_game_ms: u64,
+    #[serde(default = "default_clock_slack_ms")]
+    pub clock_slack_ms: u64,
+}
+
+fn default_max_game_ms() -> u64 {
+    4 * 60 * 60 * 1000
+}
+
+fn default_clock_slack_ms() -> u64 {
+    2_000
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        Self {
+            min_event_interval_ms: 40,
+            max_moves_per_second: 8.0,
+            max_events: 100_000,
+            max_game_ms: default_max_game_ms(),
+            clock_slack_ms: default_clock_slack_ms(),
+        }
+    }
+}
+
+// 服务端开局时发给客户端的凭据：种子由服务端生成，和一次性的 nonce 绑定，
+// issued_at_ms 为服务端发放时的时间，用来核对客户端申报的用时
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct GameTicket {
+    pub nonce: u64,
+    pub seed: u64,
+    pub issued_at_ms: u64,
+}
+
+// 已发放、尚未提交成绩的对局；每个 nonce 只能提交一次
+#[derive(Clone)]
+pub struct GameSessions {
+    pending: HashMap<u64, GameTicket>,
+    rng: StdRng,
+}
+
+impl Default for GameSessions {
+    fn default() -> Self {
+        Self {
+            pending: HashMap::new(),
+            rng: StdRng::from_entropy(),
+        }
+    }
+}
+
+impl GameSessions {
+    pub fn issue(&mut self, now_ms: u64) -> GameTicket {
+        let mut nonce = self.rng.gen();
+        while self.pending.contains_key(&nonce) {
+            nonce = self.rng.gen();
+        }
+        let ticket = GameTicket { nonce, seed: self.rng.gen(), issued_at_ms: now_ms };
+        self.pending.insert(nonce, ticket);
+        ticket
+    }
+
+    // 取出 nonce 对应的对局，取出后同一个 nonce 不能再次提交
+    pub fn take(&mut self, nonce: u64) -> Result<GameTicket, String> {
+        self.pending.remove(&nonce).ok_or_else(|| "Unknown or already used game nonce".to_string())
+    }
+
+    // 丢弃 cutoff_ms 之前发放的对局，避免放弃的对局一直占用内存
+    pub fn expire(&mut self, cutoff_ms: u64) {
+        self.pending.retain(|_, ticket| ticket.issued_at_ms >= cutoff_ms);
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+// 客户端提交成绩时附带的凭证：开局凭据的 nonce、整局录像，
+// 以及每个操作相对开局的毫秒时间戳（与 replay.events 一一对应）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScoreProof {
+    pub nonce: u64,
+    pub replay: Replay,
+    pub event_times_ms: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VerifiedGame {
+    pub score: u32,
+    pub moves: usize,
+    pub duration_ms: u64,
+}
+
+impl VerificationConfig {
+    // 排行榜只接受服务端发放的种子、经典规则、只有移动操作的对局；
+    // 先做便宜的检查，再用 2048 引擎按种子和操作序列重新模拟整局，得分必须与申报的完全一致
+    pub fn verify(&self, claimed_score: u32, proof: &ScoreProof, ticket: &GameTicket, now_ms: u64) -> Result<VerifiedGame, String> {
+        let replay = &proof.replay;
+        if proof.nonce != ticket.nonce || replay.seed != ticket.seed {
+            return Err("Replay does not belong to the issued game".to_string());
+        }
+        if replay.config != GameConfig::default() {
+            return Err("Only classic games can enter the leaderboard".to_string());
+        }
+        if replay.initial_grid.is_some() || !replay.spawn_script.is_empty() {
+            return Err("Games from a custom position cannot enter the leaderboard".to_string());
+        }
+        if let Some(event) = replay.events.iter().find(|event| !matches!(event, ReplayEvent::Move { .. })) {
+            return Err(format!("Replay contains a {:?} event, only moves are allowed", event));
+        }
+
+        let events = &replay.events;
+        if events.len() > self.max_events {
+            return Err(format!("Replay has {} events, limit is {}", events.len(), self.max_events));
+        }
+        if proof.event_times_ms.len() != events.len() {
+            return Err(format!(
+                "Replay has {} events but {} timestamps",
+                events.len(),
+                proof.event_times_ms.len()
+            ));
+        }
+        for (index, pair) in proof.event_times_ms.windows(2).enumerate() {
+            if pair[1] < pair[0] {
+                return Err(format!("Timestamp of event {} goes backwards", index + 1));
+            }
+            if pair[1] - pair[0] < self.min_event_interval_ms {
+                return Err(format!(
+                    "Event {} came {}ms after the previous one, minimum is {}ms",
+                    index + 1,
+                    pair[1] - pair[0],
+                    self.min_event_interval_ms
+                ));
+            }
+        }
+
+        // 客户端的时间线必须放得进服务端实际经过的时间里
+        let elapsed_ms = now_ms.saturating_sub(ticket.issued_at_ms);
+        if elapsed_ms > self.max_game_ms {
+            return Err(format!("Game was issued {}ms ago, limit is {}ms", elapsed_ms, self.max_game_ms));
+        }
+        let last_ms = proof.event_times_ms.last().copied().unwrap_or(0);
+        if last_ms > elapsed_ms + self.clock_slack_ms {
+            return Err(format!("Replay claims {}ms but only {}ms passed on the server", last_ms, elapsed_ms));
+        }
+
+        let moves = events.len();
+        let duration_ms = last_ms - proof.event_times_ms.first().copied().unwrap_or(0);
+        if moves as f64 > self.max_moves_per_second * duration_ms as f64 / 1000.0 + 1.0 {
+            return Err(format!("{} moves in {}ms is faster than humanly possible", moves, duration_ms));
+        }
+
+        let game = Game2048::replay(replay)?;
+        if game.score() != claimed_score {
+            return Err(format!("Claimed score {} but replay scores {}", claimed_score, game.score()));
+        }
+        Ok(VerifiedGame { score: game.score(), moves, duration_ms })
+    }
+}
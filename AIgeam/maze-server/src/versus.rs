@@ -0,0 +1,256 @@
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Serialize, Deserialize};
+use tokio::sync::mpsc;
+use wasm_2048::{Game2048, GameConfig, GameStatus, MoveResult, SpawnedTile};
+
+// 阻碍方块位置的随机数与玩家的生成序列分开，两名玩家看到的新方块序列始终来自同一个种子
+const BLOCKER_SEED_SALT: u64 = 0x9E37_79B9_7F4A_7C15;
+
+// 2048 对战规则：合并出不小于 blocker_threshold 的方块时，每次这样的合并向对手送出
+// blockers_per_merge 个数值为 blocker_value 的方块，落在对手棋盘的随机空格上
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VersusConfig {
+    pub blocker_threshold: u32,
+    pub blockers_per_merge: u32,
+    pub blocker_value: u32,
+}
+
+impl Default for VersusConfig {
+    fn default() -> Self {
+        Self {
+            blocker_threshold: 32,
+            blockers_per_merge: 1,
+            blocker_value: 2,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ClientMessage {
+    Move { direction: u8 },
+    Resign,
+}
+
+// 服务端广播给双方的消息，player 为 0 或 1
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ServerMessage {
+    Started { player: usize, seed: u64, config: VersusConfig },
+    Moved { player: usize, result: MoveResult },
+    Blockers { player: usize, tiles: Vec<SpawnedTile> },
+    Finished(VersusResult),
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FinishReason {
+    Stuck,
+    Resigned,
+    Disconnected,
+}
+
+// winner 为 None 表示平局（双方同时无路可走或全部断线）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VersusResult {
+    pub winner: Option<usize>,
+    pub reason: FinishReason,
+    pub scores: [u32; 2],
+}
+
+// 服务端权威的对局状态：客户端只提交方向，棋盘、生成和阻碍方块全部由这里决定
+pub struct VersusMatch {
+    seed: u64,
+    config: VersusConfig,
+    games: [Game2048; 2],
+    rng: ChaCha8Rng,
+    result: Option<VersusResult>,
+}
+
+impl VersusMatch {
+    pub fn new(seed: u64, config: VersusConfig) -> Self {
+        // 双方共用一个生成序列：第 k 个新方块的目标格子和数值相同，不受对方送来的阻碍方块影响
+        let game_config = GameConfig { shared_spawns: true, ..GameConfig::default() };
+        let game = || Game2048::from_config(game_config.clone(), seed).expect("default config is valid");
+        Self {
+            seed,
+            config,
+            games: [game(), game()],
+            rng: ChaCha8Rng::seed_from_u64(seed ^ BLOCKER_SEED_SALT),
+            result: None,
+        }
+    }
+
+    // 执行一名玩家的移动，返回需要广播的消息；对局结束后不再接受移动
+    pub fn play(&mut self, player: usize, direction: u8) -> Vec<ServerMessage> {
+        if self.result.is_some() || player > 1 {
+            return Vec::new();
+        }
+        let result = self.games[player].play(direction);
+        let attacks = result.merges.iter()
+            .filter(|merge| merge.value >= self.config.blocker_threshold)
+            .count() as u32;
+
+        let mut messages = vec![ServerMessage::Moved { player, result }];
+        if attacks > 0 {
+            let opponent = 1 - player;
+            let tiles = self.send_blockers(opponent, attacks * self.config.blockers_per_merge);
+            if !tiles.is_empty() {
+                messages.push(ServerMessage::Blockers { player: opponent, tiles });
+            }
+        }
+        messages.extend(self.check_stuck());
+        messages
+    }
+
+    pub fn resign(&mut self, player: usize) -> Vec<ServerMessage> {
+        if player > 1 {
+            return Vec::new();
+        }
+        self.finish(Some(1 - player), FinishReason::Resigned)
+    }
+
+    // 所有连接都已断开
+    pub fn abandon(&mut self) -> Vec<ServerMessage> {
+        self.finish(None, FinishReason::Disconnected)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn config(&self) -> &VersusConfig {
+        &self.config
+    }
+
+    pub fn game(&self, player: usize) -> Option<&Game2048> {
+        self.games.get(player)
+    }
+
+    pub fn result(&self) -> Option<&VersusResult> {
+        self.result.as_ref()
+    }
+
+    // 空格不够时多出的阻碍方块作废
+    fn send_blockers(&mut self, target: usize, count: u32) -> Vec<SpawnedTile> {
+        let game = &mut self.games[target];
+        let mut empty_cells = game.board().empty_cells();
+        empty_cells.shuffle(&mut self.rng);
+
+        empty_cells.into_iter()
+            .take(count as usize)
+            .map(|(row, col)| SpawnedTile { row: row as u8, col: col as u8, value: self.config.blocker_value })
+            .filter(|&tile| game.place_blocker(tile).is_ok())
+            .collect()
+    }
+
+    // 阻碍方块可能让对手和自己同时无路可走，这时判为平局
+    fn check_stuck(&mut self) -> Vec<ServerMessage> {
+        let stuck = [0, 1].map(|player| self.games[player].status() == GameStatus::Lost);
+        match stuck {
+            [false, false] => Vec::new(),
+            [true, true] => self.finish(None, FinishReason::Stuck),
+            [true, false] => self.finish(Some(1), FinishReason::Stuck),
+            [false, true] => self.finish(Some(0), FinishReason::Stuck),
+        }
+    }
+
+    fn finish(&mut self, winner: Option<usize>, reason: FinishReason) -> Vec<ServerMessage> {
+        if self.result.is_some() {
+            return Vec::new();
+        }
+        let result = VersusResult {
+            winner,
+            reason,
+            scores: [self.games[0].score(), self.games[1].score()],
+        };
+        self.result = Some(result.clone());
+        vec![ServerMessage::Finished(result)]
+    }
+}
+
+// 对局的服务端循环：所有客户端消息进入同一个 inbox，按到达顺序依次处理，结果广播给双方
+// 网络传输层负责把连接上的消息转发进 inbox，连接断开时代替该玩家发送 Resign
+pub struct VersusServer {
+    session: VersusMatch,
+    inbox: mpsc::UnboundedReceiver<(usize, ClientMessage)>,
+    outboxes: [mpsc::UnboundedSender<ServerMessage>; 2],
+}
+
+impl VersusServer {
+    pub fn new(
+        session: VersusMatch,
+        inbox: mpsc::UnboundedReceiver<(usize, ClientMessage)>,
+        outboxes: [mpsc::UnboundedSender<ServerMessage>; 2],
+    ) -> Self {
+        Self { session, inbox, outboxes }
+    }
+
+    pub async fn run(mut self) -> VersusResult {
+        for player in 0..2 {
+            let started = ServerMessage::Started {
+                player,
+                seed: self.session.seed(),
+                config: self.session.config().clone(),
+            };
+            let _ = self.outboxes[player].send(started);
+        }
+
+        while let Some((player, message)) = self.inbox.recv().await {
+            let messages = match message {
+                ClientMessage::Move { direction } => self.session.play(player, direction),
+                ClientMessage::Resign => self.session.resign(player),
+            };
+            self.broadcast(messages);
+            if let Some(result) = self.session.result() {
+                return result.clone();
+            }
+        }
+
+        let messages = self.session.abandon();
+        self.broadcast(messages);
+        self.session.result().cloned().expect("abandoned match has a result")
+    }
+
+    // 客户端已经断开时发送失败，直接忽略
+    fn broadcast(&self, messages: Vec<ServerMessage>) {
+        for message in messages {
+            for outbox in &self.outboxes {
+                let _ = outbox.send(message.clone());
+            }
+        }
+    }
+}
+
+// 进程内的传输层，用于测试和本地双人对战
+pub struct LocalClient {
+    player: usize,
+    tx: mpsc::UnboundedSender<(usize, ClientMessage)>,
+    rx: mpsc::UnboundedReceiver<ServerMessage>,
+}
+
+impl LocalClient {
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    pub fn send(&self, message: ClientMessage) -> Result<(), String> {
+        self.tx.send((self.player, message)).map_err(|_| "Versus server has stopped".to_string())
+    }
+
+    pub async fn recv(&mut self) -> Option<ServerMessage> {
+        self.rx.recv().await
+    }
+}
+
+pub fn local_match(seed: u64, config: VersusConfig) -> (VersusServer, [LocalClient; 2]) {
+    let (tx, inbox) = mpsc::unbounded_channel();
+    let (tx0, rx0) = mpsc::unbounded_channel();
+    let (tx1, rx1) = mpsc::unbounded_channel();
+
+    let server = VersusServer::new(VersusMatch::new(seed, config), inbox, [tx0, tx1]);
+    let clients = [
+        LocalClient { player: 0, tx: tx.clone(), rx: rx0 },
+        LocalClient { player: 1, tx, rx: rx1 },
+    ];
+    (server, clients)
+}
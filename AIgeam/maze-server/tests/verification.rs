@@ -0,0 +1,177 @@
+// 排行榜成绩校验的测试：正常对局能通过，各种伪造的录像和时间都要被拒绝
+use maze_server::leaderboard::{Leaderboard, ScoreEntry};
+use maze_server::verification::{GameSessions, GameTicket, ScoreProof, VerificationConfig};
+use wasm_2048::{Game2048, GameConfig, ReplayEvent, SpawnedTile};
+
+const START_MS: u64 = 1_000_000;
+
+// 按凭据里的种子开局，每隔 interval_ms 走一步，返回成绩和凭证
+fn play(ticket: &GameTicket, moves: usize, interval_ms: u64) -> (u32, ScoreProof) {
+    let mut game = Game2048::with_seed(ticket.seed);
+    for i in 0..moves {
+        game.play([0, 3, 0, 3, 1, 2][i % 6]);
+    }
+    let proof = ScoreProof {
+        nonce: ticket.nonce,
+        replay: game.replay_log().clone(),
+        event_times_ms: (1..=moves as u64).map(|i| i * interval_ms).collect(),
+    };
+    (game.score(), proof)
+}
+
+fn issue() -> GameTicket {
+    GameSessions::default().issue(START_MS)
+}
+
+fn verify(score: u32, proof: &ScoreProof, ticket: &GameTicket) -> Result<u64, String> {
+    let now_ms = START_MS + proof.event_times_ms.last().copied().unwrap_or(0);
+    VerificationConfig::default().verify(score, proof, ticket, now_ms).map(|game| game.duration_ms)
+}
+
+#[test]
+fn honest_game_is_accepted() {
+    let ticket = issue();
+    let (score, proof) = play(&ticket, 60, 200);
+    assert_eq!(verify(score, &proof, &ticket), Ok(59 * 200));
+}
+
+#[test]
+fn nonce_is_single_use() {
+    let mut sessions = GameSessions::default();
+    let ticket = sessions.issue(START_MS);
+    assert_eq!(sessions.take(ticket.nonce), Ok(ticket));
+    assert!(sessions.take(ticket.nonce).is_err());
+    assert!(sessions.take(ticket.nonce.wrapping_add(1)).is_err());
+
+    sessions.issue(START_MS);
+    sessions.issue(START_MS + 10);
+    sessions.expire(START_MS + 5);
+    assert_eq!(sessions.len(), 1);
+}
+
+#[test]
+fn chosen_seed_is_rejected() {
+    let ticket = issue();
+    let other = GameTicket { seed: ticket.seed ^ 1, ..ticket };
+    let (score, proof) = play(&other, 30, 200);
+    assert!(verify(score, &proof, &ticket).is_err());
+
+    let (score, mut proof) = play(&ticket, 30, 200);
+    proof.nonce ^= 1;
+    assert!(verify(score, &proof, &ticket).is_err());
+}
+
+#[test]
+fn custom_rules_are_rejected() {
+    let ticket = issue();
+    let config = GameConfig { spawn_weights: vec![0.0, 1.0], ..GameConfig::default() };
+    let mut game = Game2048::from_config(config, ticket.seed).unwrap();
+    game.play(0);
+    let proof = ScoreProof { nonce: ticket.nonce, replay: game.replay_log().clone(), event_times_ms: vec![200] };
+    assert!(verify(game.score(), &proof, &ticket).is_err());
+}
+
+#[test]
+fn custom_positions_are_rejected() {
+    let ticket = issue();
+    let (score, mut proof) = play(&ticket, 10, 200);
+    proof.replay.initial_grid = Some(vec![vec![1024, 1024, 0, 0], vec![0; 4], vec![0; 4], vec![0; 4]]);
+    assert!(verify(score, &proof, &ticket).is_err());
+
+    let (score, mut proof) = play(&ticket, 10, 200);
+    proof.replay.spawn_script = vec![SpawnedTile { row: 0, col: 0, value: 1024 }];
+    assert!(verify(score, &proof, &ticket).is_err());
+}
+
+#[test]
+fn non_move_events_are_rejected() {
+    let ticket = issue();
+    let mut game = Game2048::with_seed(ticket.seed);
+    game.play(0);
+    game.play(3);
+    game.undo();
+    game.play(1);
+    let proof = ScoreProof { nonce: ticket.nonce, replay: game.replay_log().clone(), event_times_ms: vec![200, 400, 600, 800] };
+    assert!(verify(game.score(), &proof, &ticket).is_err());
+
+    let mut game = Game2048::with_seed(ticket.seed);
+    let cell = game.board().empty_cells()[0];
+    game.place_blocker(SpawnedTile { row: cell.0 as u8, col: cell.1 as u8, value: 512 }).unwrap();
+    let proof = ScoreProof { nonce: ticket.nonce, replay: game.replay_log().clone(), event_times_ms: vec![200] };
+    assert!(verify(game.score(), &proof, &ticket).is_err());
+
+    let (score, mut proof) = play(&ticket, 10, 200);
+    proof.replay.events.push(ReplayEvent::Redo);
+    proof.event_times_ms.push(2_200);
+    assert!(verify(score, &proof, &ticket).is_err());
+}
+
+#[test]
+fn duration_is_measured_from_the_first_event() {
+    let ticket = issue();
+    // 时间戳整体后移并不能让过快的操作显得合理
+    let (score, mut proof) = play(&ticket, 60, 50);
+    for time in &mut proof.event_times_ms {
+        *time += 600_000;
+    }
+    assert!(verify(score, &proof, &ticket).is_err());
+}
+
+#[test]
+fn timeline_must_fit_in_server_time() {
+    let ticket = issue();
+    let (score, proof) = play(&ticket, 60, 200);
+    let config = VerificationConfig::default();
+    // 服务端只过了 1 秒，录像却声称用了 12 秒
+    assert!(config.verify(score, &proof, &ticket, START_MS + 1_000).is_err());
+    assert!(config.verify(score, &proof, &ticket, START_MS + config.max_game_ms + 1).is_err());
+    assert!(config.verify(score, &proof, &ticket, START_MS + 12_000).is_ok());
+}
+
+#[test]
+fn wrong_score_is_rejected() {
+    let ticket = issue();
+    let (score, proof) = play(&ticket, 60, 200);
+    assert!(verify(score + 4, &proof, &ticket).is_err());
+}
+
+fn entry(score: u32) -> ScoreEntry {
+    ScoreEntry {
+        player_id: "player-1".to_string(),
+        player_name: "Ada".to_string(),
+        score,
+        timestamp_ms: START_MS,
+        game_mode: "classic".to_string(),
+    }
+}
+
+#[test]
+fn leaderboard_accepts_an_honest_replay_once() {
+    let mut leaderboard = Leaderboard::new();
+    let ticket = leaderboard.start_game(START_MS);
+    let (score, proof) = play(&ticket, 60, 200);
+    assert_eq!(leaderboard.add_score(entry(score), &proof, START_MS + 12_000), Ok(()));
+    assert_eq!(leaderboard.get_top_scores(10), vec![&entry(score)]);
+
+    // 同一个 nonce 不能再提交第二次
+    assert!(leaderboard.add_score(entry(score), &proof, START_MS + 13_000).is_err());
+    assert_eq!(leaderboard.get_top_scores(10).len(), 1);
+}
+
+#[test]
+fn leaderboard_rejects_a_wrong_score() {
+    let mut leaderboard = Leaderboard::new();
+    let ticket = leaderboard.start_game(START_MS);
+    let (score, proof) = play(&ticket, 60, 200);
+    assert!(leaderboard.add_score(entry(score + 4), &proof, START_MS + 12_000).is_err());
+    assert!(leaderboard.get_top_scores(10).is_empty());
+}
+
+#[test]
+fn leaderboard_rejects_tickets_it_did_not_issue() {
+    let mut leaderboard = Leaderboard::new();
+    leaderboard.start_game(START_MS);
+    let ticket = issue();
+    let (score, proof) = play(&ticket, 60, 200);
+    assert!(leaderboard.add_score(entry(score), &proof, START_MS + 12_000).is_err());
+}
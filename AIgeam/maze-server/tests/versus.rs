@@ -0,0 +1,48 @@
+// 对战模式的测试：通过进程内的传输层完整地打一局
+use maze_server::versus::{local_match, ClientMessage, FinishReason, ServerMessage, VersusConfig, VersusMatch};
+
+#[test]
+fn unknown_players_are_ignored() {
+    let mut session = VersusMatch::new(1, VersusConfig::default());
+    assert!(session.play(2, 0).is_empty());
+    assert!(session.resign(2).is_empty());
+    assert!(session.result().is_none());
+    assert!(session.game(2).is_none());
+}
+
+#[tokio::test]
+async fn local_match_plays_until_resignation() {
+    let config = VersusConfig { blocker_threshold: 4, ..VersusConfig::default() };
+    let (server, [mut first, mut second]) = local_match(5, config.clone());
+    let server = tokio::spawn(server.run());
+
+    for client in [&mut first, &mut second] {
+        let started = client.recv().await.unwrap();
+        assert_eq!(started, ServerMessage::Started { player: client.player(), seed: 5, config: config.clone() });
+    }
+
+    // 玩家 0 走几步，双方都收到同样的广播，合并出 4 时玩家 1 收到阻碍方块
+    let mut blockers = 0;
+    for direction in [0, 3, 0, 3, 1, 2, 0, 3] {
+        first.send(ClientMessage::Move { direction }).unwrap();
+        let moved = first.recv().await.unwrap();
+        assert!(matches!(moved, ServerMessage::Moved { player: 0, .. }));
+        assert_eq!(second.recv().await.unwrap(), moved);
+        if let ServerMessage::Moved { result, .. } = moved {
+            if result.merges.iter().any(|merge| merge.value >= 4) {
+                let sent = first.recv().await.unwrap();
+                assert!(matches!(sent, ServerMessage::Blockers { player: 1, .. }));
+                assert_eq!(second.recv().await.unwrap(), sent);
+                blockers += 1;
+            }
+        }
+    }
+    assert!(blockers > 0);
+
+    second.send(ClientMessage::Resign).unwrap();
+    let result = server.await.unwrap();
+    assert_eq!(result.winner, Some(0));
+    assert_eq!(result.reason, FinishReason::Resigned);
+    assert_eq!(first.recv().await.unwrap(), ServerMessage::Finished(result.clone()));
+    assert_eq!(second.recv().await.unwrap(), ServerMessage::Finished(result));
+}
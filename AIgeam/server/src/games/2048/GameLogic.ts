@@ -1,78 +1,41 @@
+import { nativeRules, NativeRules, Direction } from './NativeRules';
+
+// 移动、合并、生成和胜负判断全部交给 Rust 规则库，与 wasm 客户端和 AI 使用同一份规则
 export class Game2048 {
   private board: number[][];
   private size: number;
   private score: number;
   private highScore: number = 0;
+  private rules: NativeRules;
 
-  constructor(size: number = 4) {
+  private constructor(size: number, rules: NativeRules) {
     this.size = size;
+    this.rules = rules;
     this.board = Array.from({ length: size }, () => 
       Array.from({ length: size }, () => 0)
     );
     this.score = 0;
-    this.addRandomTile();
-    this.addRandomTile();
   }
 
-  private addRandomTile(): void {
-    const emptyCells: [number, number][] = [];
-    for (let row = 0; row < this.size; row++) {
-      for (let col = 0; col < this.size; col++) {
-        if (this.board[row][col] === 0) {
-          emptyCells.push([row, col]);
-        }
-      }
-    }
-
-    if (emptyCells.length > 0) {
-      const [row, col] = emptyCells[Math.floor(Math.random() * emptyCells.length)];
-      this.board[row][col] = Math.random() < 0.9 ? 2 : 4;
-    }
+  static async create(size: number = 4, rules: NativeRules = nativeRules()): Promise<Game2048> {
+    const game = new Game2048(size, rules);
+    await game.addRandomTile();
+    await game.addRandomTile();
+    return game;
   }
 
-  private slide(row: number[]): number[] {
-    let arr = row.filter(val => val !== 0);
-    for (let i = 0; i < arr.length - 1; i++) {
-      if (arr[i] === arr[i + 1]) {
-        arr[i] *= 2;
-        this.score += arr[i];
-        arr.splice(i + 1, 1);
-      }
-    }
-    while (arr.length < this.size) {
-      arr.push(0);
-    }
-    return arr;
+  private async addRandomTile(): Promise<void> {
+    const { grid } = await this.rules.spawnTile(this.board);
+    this.board = grid;
   }
 
-  move(direction: 'up' | 'down' | 'left' | 'right'): boolean {
-    let moved = false;
-    const oldBoard = this.board.map(row => [...row]);
-
-    if (direction === 'left' || direction === 'right') {
-      for (let row = 0; row < this.size; row++) {
-        let arr = this.board[row];
-        if (direction === 'right') arr = arr.reverse();
-        arr = this.slide(arr);
-        if (direction === 'right') arr = arr.reverse();
-        if (!arraysEqual(arr, this.board[row])) moved = true;
-        this.board[row] = arr;
-      }
-    } else {
-      for (let col = 0; col < this.size; col++) {
-        let arr = this.board.map(row => row[col]);
-        if (direction === 'down') arr = arr.reverse();
-        arr = this.slide(arr);
-        if (direction === 'down') arr = arr.reverse();
-        if (!arraysEqual(arr, this.board.map(row => row[col]))) moved = true;
-        for (let row = 0; row < this.size; row++) {
-          this.board[row][col] = arr[row];
-        }
-      }
-    }
+  async move(direction: Direction): Promise<boolean> {
+    const { grid, moved, score } = await this.rules.move(this.board, direction);
 
     if (moved) {
-      this.addRandomTile();
+      this.board = grid;
+      this.score += score;
+      await this.addRandomTile();
       if (this.score > this.highScore) {
         this.highScore = this.score;
       }
@@ -104,7 +67,7 @@ export class Game2048 {
           userId,
           '2048',
           this.score,
-          this.hasWon()
+          await this.hasWon()
         );
       } catch (error) {
         console.error('Failed to save high score:', error);
@@ -114,30 +77,12 @@ export class Game2048 {
     }
   }
 
-  // 添加判断是否获胜的方法（通常2048游戏是达到2048瓦片）
-  hasWon(): boolean {
-    for (let row = 0; row < this.size; row++) {
-      for (let col = 0; col < this.size; col++) {
-        if (this.board[row][col] >= 2048) {
-          return true;
-        }
-      }
-    }
-    return false;
+  // 棋盘上有 2048 方块即为获胜，即使之后已经无路可走
+  async hasWon(): Promise<boolean> {
+    return (await this.rules.status(this.board, 2048)).won;
   }
 
-  isGameOver(): boolean {
-    for (let row = 0; row < this.size; row++) {
-      for (let col = 0; col < this.size; col++) {
-        if (this.board[row][col] === 0) return false;
-        if (col < this.size - 1 && this.board[row][col] === this.board[row][col + 1]) return false;
-        if (row < this.size - 1 && this.board[row][col] === this.board[row + 1][col]) return false;
-      }
-    }
-    return true;
+  async isGameOver(): Promise<boolean> {
+    return (await this.rules.status(this.board)).status === 'Lost';
   }
-}
-
-function arraysEqual(a: any[], b: any[]): boolean {
-  return a.length === b.length && a.every((val, index) => val === b[index]);
 } 
\ No newline at end of file
@@ -0,0 +1,103 @@
+import { spawn, ChildProcessWithoutNullStreams } from 'child_process';
+import { createInterface } from 'readline';
+
+export type Direction = 'up' | 'down' | 'left' | 'right';
+export type RulesStatus = 'Playing' | 'Won' | 'ContinuedAfterWin' | 'Lost';
+
+export interface MoveOutcome {
+  grid: number[][];
+  moved: boolean;
+  score: number;
+}
+
+export interface SpawnOutcome {
+  grid: number[][];
+  spawned: { row: number; col: number; value: number } | null;
+}
+
+// won 表示棋盘上已有目标方块，与 status 无关：无路可走但已经合成出目标方块时 status 为 Lost、won 为 true
+export interface StatusOutcome {
+  status: RulesStatus;
+  won: boolean;
+}
+
+// 2048 规则统一由 Rust 规则库实现（games/2048/core），这里通过 game2048-cli 子进程调用，
+// 每行一个 JSON 请求和应答，按顺序一一对应
+// 子进程启动失败、写入失败（EPIPE）或退出后，未完成和之后的请求都会立即失败，由 nativeRules() 重新启动
+export class NativeRules {
+  private process: ChildProcessWithoutNullStreams;
+  private pending: Array<{ resolve: (value: any) => void; reject: (error: Error) => void }> = [];
+  private failure: Error | null = null;
+
+  constructor(binary: string = process.env.GAME2048_CLI || 'game2048-cli') {
+    this.process = spawn(binary, [], { stdio: ['pipe', 'pipe', 'pipe'] });
+
+    createInterface({ input: this.process.stdout }).on('line', line => {
+      const request = this.pending.shift();
+      if (!request) return;
+      try {
+        const response = JSON.parse(line);
+        if (response.error) {
+          request.reject(new Error(response.error));
+        } else {
+          request.resolve(response);
+        }
+      } catch (error) {
+        request.reject(error as Error);
+      }
+    });
+
+    this.process.on('error', error => this.fail(new Error(`game2048-cli failed: ${error.message}`)));
+    this.process.stdin.on('error', error => this.fail(new Error(`game2048-cli stdin failed: ${error.message}`)));
+    this.process.on('exit', code => this.fail(new Error(`game2048-cli exited with code ${code}`)));
+  }
+
+  get alive(): boolean {
+    return this.failure === null;
+  }
+
+  move(grid: number[][], direction: Direction): Promise<MoveOutcome> {
+    return this.request({ op: 'move', grid, direction });
+  }
+
+  spawnTile(grid: number[][], seed: number = Math.floor(Math.random() * Number.MAX_SAFE_INTEGER)): Promise<SpawnOutcome> {
+    return this.request({ op: 'spawn', grid, seed });
+  }
+
+  status(grid: number[][], winTile: number = 2048): Promise<StatusOutcome> {
+    return this.request({ op: 'status', grid, win_tile: winTile });
+  }
+
+  close(): void {
+    this.fail(new Error('game2048-cli was closed'));
+    this.process.stdin.end();
+  }
+
+  private request(payload: object): Promise<any> {
+    if (this.failure) {
+      return Promise.reject(this.failure);
+    }
+    return new Promise((resolve, reject) => {
+      this.pending.push({ resolve, reject });
+      this.process.stdin.write(JSON.stringify(payload) + '\n');
+    });
+  }
+
+  // 只记录第一次失败，后续的 error 和 exit 事件不再重复处理
+  private fail(error: Error): void {
+    if (!this.failure) {
+      this.failure = error;
+    }
+    this.pending.splice(0).forEach(request => request.reject(this.failure as Error));
+  }
+}
+
+let shared: NativeRules | null = null;
+
+// 整个服务进程共用一个规则子进程，子进程失效后下一次调用时重新启动
+export function nativeRules(): NativeRules {
+  if (!shared || !shared.alive) {
+    shared = new NativeRules();
+  }
+  return shared;
+}